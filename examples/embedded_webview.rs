@@ -1,10 +1,8 @@
 use iced::{
-    time,
     widget::{button, column, container, row, text},
     Element, Length, Subscription, Task,
 };
 use iced_webview::{Action, PageType, WebView};
-use std::time::Duration;
 
 #[cfg(feature = "cef")]
 type Engine = iced_webview::Cef;
@@ -137,8 +135,6 @@ impl App {
     }
 
     fn subscription(&self) -> Subscription<Message> {
-        time::every(Duration::from_millis(10))
-            .map(|_| Action::Update)
-            .map(Message::WebView)
+        self.webview.subscription().map(Message::WebView)
     }
 }
@@ -1,6 +1,5 @@
-use iced::{time, Element, Subscription, Task};
+use iced::{Element, Subscription, Task};
 use iced_webview::{Action, PageType, WebView};
-use std::time::Duration;
 
 #[cfg(feature = "cef")]
 type Engine = iced_webview::Cef;
@@ -76,8 +75,6 @@ impl App {
     }
 
     fn subscription(&self) -> Subscription<Message> {
-        time::every(Duration::from_millis(10))
-            .map(|_| Action::Update)
-            .map(Message::WebView)
+        self.webview.subscription().map(Message::WebView)
     }
 }
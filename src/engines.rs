@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use crate::ImageInfo;
+use crate::{EngineEvent, ImageInfo, ScriptId, Tile, Waker, WebViewError};
 use iced::keyboard;
 use iced::mouse::{self, Interaction};
 use iced::Point;
@@ -22,6 +22,29 @@ pub mod servo;
 #[cfg(feature = "cef")]
 pub mod cef_engine;
 
+/// An Engine that routes each view to one of the compiled-in engines
+#[cfg(any(
+    feature = "litehtml",
+    feature = "blitz",
+    feature = "servo",
+    feature = "cef"
+))]
+pub mod any;
+
+#[cfg(any(
+    feature = "litehtml",
+    feature = "blitz",
+    feature = "servo",
+    feature = "cef"
+))]
+mod load;
+
+#[cfg(any(feature = "litehtml", feature = "blitz"))]
+mod tiles;
+
+/// Runs an Engine on a worker thread, off the iced update loop
+pub mod host;
+
 /// Creation of new pages to be of a html type or a url
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 pub enum PageType {
@@ -32,11 +55,51 @@ pub enum PageType {
 }
 
 /// Enables browser engines to display their images in different formats
+///
+/// Frames keep the engine's layout; the shader widget converts them on the
+/// GPU and only the `image::Handle` path converts them on the CPU.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum PixelFormat {
     /// RGBA
+    #[default]
     Rgba,
     /// BGRA
     Bgra,
+    /// RGBA with premultiplied alpha
+    RgbaPremultiplied,
+    /// BGRA with premultiplied alpha
+    BgraPremultiplied,
+}
+
+impl PixelFormat {
+    /// Blue and red are swapped relative to RGBA.
+    pub fn is_bgra(self) -> bool {
+        matches!(self, PixelFormat::Bgra | PixelFormat::BgraPremultiplied)
+    }
+
+    /// Colour channels are multiplied by alpha.
+    pub fn is_premultiplied(self) -> bool {
+        matches!(
+            self,
+            PixelFormat::RgbaPremultiplied | PixelFormat::BgraPremultiplied
+        )
+    }
+}
+
+/// What an engine supports, so apps generic over [`Engine`] can adapt their UI.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Capabilities {
+    /// Runs page JavaScript.
+    pub javascript: bool,
+    /// Keeps navigation history for `go_back` / `go_forward`.
+    pub history: bool,
+    /// Handles keyboard events, e.g. typing into form fields.
+    pub keyboard_input: bool,
+    /// Exposes the text selection through `get_selected_text`.
+    pub text_selection: bool,
+    /// Fetches URLs itself; see [`Engine::handles_urls`].
+    pub native_urls: bool,
 }
 
 /// Alias of usize used for controlling specific views
@@ -77,12 +140,53 @@ pub trait Engine {
     /// Set the display scale factor for HiDPI rendering. Default is no-op.
     fn set_scale_factor(&mut self, _scale: f32) {}
 
+    /// Receive the waker of the widget driving this engine. Engines whose
+    /// work arrives asynchronously call [`Waker::wake`] so the widget's
+    /// subscription can sleep while [`needs_update`](Engine::needs_update)
+    /// is `false`. Default is no-op.
+    fn set_waker(&mut self, _waker: Waker) {}
+
+    /// Receive the widget's [`FetchMode`](crate::FetchMode) and
+    /// [`RetryPolicy`](crate::RetryPolicy). Engines that fetch sub-resources
+    /// themselves (Blitz) apply them to documents loaded afterwards.
+    /// Default is no-op.
+    #[cfg(any(feature = "litehtml", feature = "blitz"))]
+    fn set_fetch_policy(&mut self, _mode: crate::FetchMode, _retry: crate::RetryPolicy) {}
+
+    /// Whether `update` should be called again soon, e.g. while resources
+    /// are draining or a frame is waiting to be captured. The widgets poll
+    /// at a fast rate while this holds and otherwise wait for the waker.
+    /// Defaults to `true`, which keeps polling.
+    fn needs_update(&self) -> bool {
+        true
+    }
+
     /// Whether this engine can fetch and render URLs natively.
     /// Engines that return `false` rely on the webview layer to fetch HTML.
     fn handles_urls(&self) -> bool {
         true
     }
 
+    /// Whether the engine behind this view fetches URLs natively. Only
+    /// differs from `handles_urls` for engines that mix backends.
+    fn view_handles_urls(&self, _id: ViewId) -> bool {
+        self.handles_urls()
+    }
+
+    /// What this engine supports. The default only reports `handles_urls`.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            native_urls: self.handles_urls(),
+            ..Capabilities::default()
+        }
+    }
+
+    /// What the engine behind this view supports. Only differs from
+    /// `capabilities` for engines that mix backends.
+    fn view_capabilities(&self, _id: ViewId) -> Capabilities {
+        self.capabilities()
+    }
+
     /// lets the engine handle keyboard events
     fn handle_keyboard_event(&mut self, id: ViewId, event: keyboard::Event);
     /// lets the engine handle mouse events
@@ -98,6 +202,32 @@ pub trait Engine {
     fn go_forward(&mut self, id: ViewId);
     /// Moves back on view
     fn go_back(&mut self, id: ViewId);
+    /// Whether the view has history to go back to.
+    fn can_go_back(&self, _id: ViewId) -> bool {
+        false
+    }
+    /// Whether the view has history to go forward to.
+    fn can_go_forward(&self, _id: ViewId) -> bool {
+        false
+    }
+    /// Whether the view is loading a page or its subresources.
+    fn is_loading(&self, _id: ViewId) -> bool {
+        false
+    }
+
+    /// Run `script` in the view's page. It is evaluated like `eval`, so the
+    /// value of its last expression statement is the result, which arrives
+    /// later as a [`WebViewEvent::ScriptResult`](crate::WebViewEvent::ScriptResult)
+    /// carrying `script_id`. Engines without JavaScript return
+    /// [`WebViewError::Unsupported`].
+    fn evaluate_script(
+        &mut self,
+        _id: ViewId,
+        _script_id: ScriptId,
+        _script: String,
+    ) -> Result<(), WebViewError> {
+        Err(WebViewError::Unsupported("JavaScript evaluation".into()))
+    }
 
     /// Gets current url from view
     fn get_url(&self, id: ViewId) -> String;
@@ -105,9 +235,16 @@ pub trait Engine {
     fn get_title(&self, id: ViewId) -> String;
     /// Gets current cursor status from view
     fn get_cursor(&self, id: ViewId) -> Interaction;
-    /// Gets CPU-rendered webview
+    /// Gets CPU-rendered webview. Tiling engines return the topmost visible tile.
     fn get_view(&self, id: ViewId) -> &ImageInfo;
 
+    /// Tiles covering the viewport, top to bottom, for engines that rasterize
+    /// the document in bands around the scroll position. When non-empty the
+    /// widgets composite these instead of drawing `get_view`.
+    fn get_tiles(&self, _id: ViewId) -> &[Tile] {
+        &[]
+    }
+
     /// Current vertical scroll offset (logical pixels).
     fn get_scroll_y(&self, _id: ViewId) -> f32 {
         0.0
@@ -149,6 +286,13 @@ pub trait Engine {
         Vec::new()
     }
 
+    /// Drain state changes since the last call: navigation, load progress,
+    /// title, cursor, content size, selection, favicon and errors. The widgets dispatch
+    /// them to `on_event` and the matching convenience callbacks.
+    fn take_events(&mut self) -> Vec<EngineEvent> {
+        Vec::new()
+    }
+
     /// Pre-load a CSS cache into a view's container so `import_css` can
     /// resolve stylesheets without network access during parsing.
     fn set_css_cache(&mut self, _id: ViewId, _cache: HashMap<String, String>) {}
@@ -166,6 +310,10 @@ pub trait Engine {
     ) {
     }
 
+    /// Tell the engine that fetching an image from `take_pending_images`
+    /// failed, so it stops waiting for it before reporting the load finished.
+    fn image_fetch_failed(&mut self, _id: ViewId, _url: &str) {}
+
     /// Flush all staged images into the document and redraw.
     /// Called when all in-flight image fetches have completed so the
     /// full batch is processed in a single redraw.
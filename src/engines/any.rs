@@ -0,0 +1,979 @@
+use std::collections::HashMap;
+
+use iced::keyboard;
+use iced::mouse::{self, Interaction};
+use iced::{Point, Size};
+use rand::Rng;
+
+use super::{Capabilities, Engine, PageType, ViewId};
+use crate::{EngineEvent, ImageInfo, ScriptId, Tile, Waker, WebViewError};
+
+#[cfg(feature = "blitz")]
+use super::blitz::Blitz;
+#[cfg(feature = "cef")]
+use super::cef_engine::Cef;
+#[cfg(feature = "litehtml")]
+use super::litehtml::Litehtml;
+#[cfg(feature = "servo")]
+use super::servo::Servo;
+
+/// The compiled-in engines [`AnyEngine`] can route a view to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EngineKind {
+    /// [`Litehtml`](crate::Litehtml)
+    #[cfg(feature = "litehtml")]
+    Litehtml,
+    /// [`Blitz`](crate::Blitz)
+    #[cfg(feature = "blitz")]
+    Blitz,
+    /// [`Servo`](crate::Servo)
+    #[cfg(feature = "servo")]
+    Servo,
+    /// [`Cef`](crate::Cef)
+    #[cfg(feature = "cef")]
+    Cef,
+}
+
+/// Preference order for pages loaded from a URL: full browsers first.
+const URL_PREFERENCE: &[EngineKind] = &[
+    #[cfg(feature = "servo")]
+    EngineKind::Servo,
+    #[cfg(feature = "cef")]
+    EngineKind::Cef,
+    #[cfg(feature = "blitz")]
+    EngineKind::Blitz,
+    #[cfg(feature = "litehtml")]
+    EngineKind::Litehtml,
+];
+
+/// Preference order for raw HTML: lightweight engines first.
+const HTML_PREFERENCE: &[EngineKind] = &[
+    #[cfg(feature = "litehtml")]
+    EngineKind::Litehtml,
+    #[cfg(feature = "blitz")]
+    EngineKind::Blitz,
+    #[cfg(feature = "servo")]
+    EngineKind::Servo,
+    #[cfg(feature = "cef")]
+    EngineKind::Cef,
+];
+
+impl EngineKind {
+    /// Whether the engine runs page JavaScript.
+    pub fn has_javascript(self) -> bool {
+        match self {
+            #[cfg(feature = "litehtml")]
+            EngineKind::Litehtml => false,
+            #[cfg(feature = "blitz")]
+            EngineKind::Blitz => false,
+            #[cfg(feature = "servo")]
+            EngineKind::Servo => true,
+            #[cfg(feature = "cef")]
+            EngineKind::Cef => true,
+        }
+    }
+
+    /// The first compiled-in engine that runs JavaScript, if any.
+    fn javascript() -> Option<EngineKind> {
+        URL_PREFERENCE.iter().copied().find(|k| k.has_javascript())
+    }
+}
+
+/// The default route: URLs go to a full browser, raw HTML to a lightweight
+/// engine, each falling back to whatever else is compiled in.
+fn default_route(page: &PageType) -> EngineKind {
+    match page {
+        PageType::Url(_) => URL_PREFERENCE[0],
+        PageType::Html(_) => HTML_PREFERENCE[0],
+    }
+}
+
+/// Whether the page would only work with JavaScript enabled: it has a
+/// `<script>` element a browser would run.
+fn needs_javascript(html: &str) -> bool {
+    let html = html.to_ascii_lowercase();
+    start_tags(&html).iter().any(|tag| {
+        tag.name == "script"
+            && attribute(tag.attrs, "type").is_none_or(|t| {
+                let t = t.trim();
+                t.is_empty()
+                    || t == "module"
+                    || t.ends_with("javascript")
+                    || t.ends_with("ecmascript")
+            })
+    })
+}
+
+/// `html` with a `<base>` element pointing at `url`, so a page handed to
+/// another engine as HTML still resolves its relative links and resources.
+fn with_base(html: &str, url: &str) -> String {
+    let lower = html.to_ascii_lowercase();
+    let tags = start_tags(&lower);
+    if tags.iter().any(|tag| tag.name == "base") {
+        return html.to_string();
+    }
+    let at = tags
+        .iter()
+        .find(|tag| tag.name == "head")
+        .or_else(|| tags.iter().find(|tag| tag.name == "html"))
+        .map(|tag| tag.end)
+        .or_else(|| tags.first().map(|tag| tag.start))
+        .unwrap_or(0);
+    let href = url.replace('&', "&amp;").replace('"', "&quot;");
+    format!("{}<base href=\"{href}\">{}", &html[..at], &html[at..])
+}
+
+/// A start tag found by [`start_tags`].
+struct StartTag<'a> {
+    name: &'a str,
+    /// Everything between the name and the closing `>`.
+    attrs: &'a str,
+    /// Byte offset of the `<`.
+    start: usize,
+    /// Byte offset just past the `>`.
+    end: usize,
+}
+
+/// Elements whose content is text up to their end tag, not markup.
+const RAW_TEXT: &[&str] = &[
+    "script",
+    "style",
+    "textarea",
+    "title",
+    "xmp",
+    "iframe",
+    "noembed",
+    "noframes",
+    "noscript",
+    "plaintext",
+];
+
+/// The start tags of lowercased `html` in document order. Comments,
+/// declarations, end tags, quoted attribute values and the content of
+/// raw-text elements are skipped the way an HTML tokenizer skips them.
+fn start_tags(html: &str) -> Vec<StartTag<'_>> {
+    let bytes = html.as_bytes();
+    let mut tags = Vec::new();
+    let mut i = 0;
+    while let Some(offset) = html[i..].find('<') {
+        let start = i + offset;
+        let rest = &html[start + 1..];
+        if rest.starts_with("!--") {
+            match html[start + 4..].find("-->") {
+                Some(close) => i = start + 4 + close + 3,
+                None => break,
+            }
+            continue;
+        }
+        if !rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            // End tags, declarations and processing instructions run to the
+            // next `>`; a stray `<` is text.
+            i = if rest.starts_with(['/', '!', '?']) {
+                match rest.find('>') {
+                    Some(close) => start + 1 + close + 1,
+                    None => break,
+                }
+            } else {
+                start + 1
+            };
+            continue;
+        }
+
+        let name_end = rest
+            .find(|c: char| c.is_ascii_whitespace() || c == '/' || c == '>')
+            .map_or(html.len(), |n| start + 1 + n);
+        let mut j = name_end;
+        let mut quote = None;
+        let mut after_equals = false;
+        while j < bytes.len() {
+            let b = bytes[j];
+            match quote {
+                Some(q) if b == q => quote = None,
+                Some(_) => {}
+                None if b == b'>' => break,
+                None if after_equals && (b == b'"' || b == b'\'') => quote = Some(b),
+                None => {}
+            }
+            if quote.is_none() && !b.is_ascii_whitespace() {
+                after_equals = b == b'=';
+            }
+            j += 1;
+        }
+        if j == bytes.len() {
+            break;
+        }
+        let name = &html[start + 1..name_end];
+        tags.push(StartTag {
+            name,
+            attrs: &html[name_end..j],
+            start,
+            end: j + 1,
+        });
+        i = j + 1;
+
+        if RAW_TEXT.contains(&name) {
+            let close = format!("</{name}");
+            match html[i..].find(&close) {
+                Some(offset) => i += offset,
+                None => break,
+            }
+        }
+    }
+    tags
+}
+
+/// The value of attribute `name` in a start tag's attribute text, or an
+/// empty string for an attribute without a value.
+fn attribute<'a>(attrs: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = attrs;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == '/');
+        if rest.is_empty() {
+            return None;
+        }
+        let key_end = rest
+            .find(|c: char| c.is_ascii_whitespace() || c == '=' || c == '/')
+            .unwrap_or(rest.len());
+        let key = &rest[..key_end];
+        rest = rest[key_end..].trim_start();
+        let mut value = "";
+        if let Some(after) = rest.strip_prefix('=') {
+            let after = after.trim_start();
+            match after.chars().next() {
+                Some(q @ ('"' | '\'')) => {
+                    let body = &after[1..];
+                    let close = body.find(q).unwrap_or(body.len());
+                    value = &body[..close];
+                    rest = body.get(close + 1..).unwrap_or("");
+                }
+                _ => {
+                    let end = after
+                        .find(|c: char| c.is_ascii_whitespace())
+                        .unwrap_or(after.len());
+                    value = &after[..end];
+                    rest = &after[end..];
+                }
+            }
+        }
+        if key == name {
+            return Some(value);
+        }
+    }
+}
+
+/// Run `$body` against the backend for `$kind`, if it has been created.
+/// Evaluates to `Option` of the body's value.
+macro_rules! dispatch {
+    ($self:ident . $as:ident ( $kind:expr ), |$engine:ident| $body:expr) => {
+        match $kind {
+            #[cfg(feature = "litehtml")]
+            EngineKind::Litehtml => $self.litehtml.$as().map(|$engine| $body),
+            #[cfg(feature = "blitz")]
+            EngineKind::Blitz => $self.blitz.$as().map(|$engine| $body),
+            #[cfg(feature = "servo")]
+            EngineKind::Servo => $self.servo.$as().map(|$engine| $body),
+            #[cfg(feature = "cef")]
+            EngineKind::Cef => $self.cef.$as().map(|$engine| $body),
+        }
+    };
+}
+
+/// Run `$body` against every backend that has been created.
+macro_rules! for_each_backend {
+    ($self:ident . $as:ident (), |$engine:ident| $body:expr) => {
+        #[cfg(feature = "litehtml")]
+        if let Some($engine) = $self.litehtml.$as() {
+            $body;
+        }
+        #[cfg(feature = "blitz")]
+        if let Some($engine) = $self.blitz.$as() {
+            $body;
+        }
+        #[cfg(feature = "servo")]
+        if let Some($engine) = $self.servo.$as() {
+            $body;
+        }
+        #[cfg(feature = "cef")]
+        if let Some($engine) = $self.cef.$as() {
+            $body;
+        }
+    };
+}
+
+struct AnyView {
+    id: ViewId,
+    kind: EngineKind,
+    /// The id the owning backend knows the view by.
+    inner: ViewId,
+}
+
+/// Engine that routes each view to one of the compiled-in engines.
+///
+/// The engine is picked per view when it is created, by a router over the
+/// initial [`PageType`]. By default URLs open in Servo or CEF and raw HTML in
+/// litehtml or Blitz, depending on which features are enabled. Backends are
+/// created on first use, so an unused Servo or CEF costs nothing.
+///
+/// With the JavaScript fallback on (the default), HTML containing `<script>`
+/// that was routed to an engine without JavaScript moves to a JS-capable one.
+/// A view keeps its `ViewId` when it moves.
+pub struct AnyEngine {
+    #[cfg(feature = "litehtml")]
+    litehtml: Option<Litehtml>,
+    #[cfg(feature = "blitz")]
+    blitz: Option<Blitz>,
+    #[cfg(feature = "servo")]
+    servo: Option<Servo>,
+    #[cfg(feature = "cef")]
+    cef: Option<Cef>,
+    views: Vec<AnyView>,
+    router: Box<dyn Fn(&PageType) -> EngineKind>,
+    js_fallback: bool,
+    size: Option<Size<u32>>,
+    scale_factor: f32,
+    /// Handed to every backend, including ones created later.
+    waker: Option<Waker>,
+    /// Likewise handed to every backend, if the widget set one.
+    #[cfg(any(feature = "litehtml", feature = "blitz"))]
+    fetch_policy: Option<(crate::FetchMode, crate::RetryPolicy)>,
+    /// Events of views that moved to another backend, drained first.
+    events: Vec<EngineEvent>,
+}
+
+impl Default for AnyEngine {
+    fn default() -> Self {
+        Self {
+            #[cfg(feature = "litehtml")]
+            litehtml: None,
+            #[cfg(feature = "blitz")]
+            blitz: None,
+            #[cfg(feature = "servo")]
+            servo: None,
+            #[cfg(feature = "cef")]
+            cef: None,
+            views: Vec::new(),
+            router: Box::new(default_route),
+            js_fallback: true,
+            size: None,
+            scale_factor: 1.0,
+            waker: None,
+            #[cfg(any(feature = "litehtml", feature = "blitz"))]
+            fetch_policy: None,
+            events: Vec::new(),
+        }
+    }
+}
+
+impl AnyEngine {
+    /// Pick the engine for each new view from its initial content.
+    /// Views created without content are routed as an empty HTML page.
+    pub fn with_router(router: impl Fn(&PageType) -> EngineKind + 'static) -> Self {
+        Self {
+            router: Box::new(router),
+            ..Self::default()
+        }
+    }
+
+    /// Move pages that contain `<script>` to a JS-capable engine. On by default.
+    pub fn javascript_fallback(mut self, enabled: bool) -> Self {
+        self.js_fallback = enabled;
+        self
+    }
+
+    /// The engine a view is running in.
+    pub fn engine_kind(&self, id: ViewId) -> Option<EngineKind> {
+        self.find_view(id).map(|v| v.kind)
+    }
+
+    fn find_view(&self, id: ViewId) -> Option<&AnyView> {
+        self.views.iter().find(|v| v.id == id)
+    }
+
+    fn route(&self, id: ViewId) -> Option<(EngineKind, ViewId)> {
+        self.find_view(id).map(|v| (v.kind, v.inner))
+    }
+
+    fn outer_id(&self, kind: EngineKind, inner: ViewId) -> Option<ViewId> {
+        self.views
+            .iter()
+            .find(|v| v.kind == kind && v.inner == inner)
+            .map(|v| v.id)
+    }
+
+    /// Create the backend for `kind` if needed, matching the current size
+    /// and scale factor.
+    fn ensure_backend(&mut self, kind: EngineKind) {
+        fn init<E: Engine + Default>(
+            slot: &mut Option<E>,
+            size: Option<Size<u32>>,
+            scale: f32,
+            waker: Option<&Waker>,
+        ) -> bool {
+            if slot.is_some() {
+                return false;
+            }
+            let mut engine = E::default();
+            engine.set_scale_factor(scale);
+            if let Some(size) = size {
+                engine.resize(size);
+            }
+            if let Some(waker) = waker {
+                engine.set_waker(waker.clone());
+            }
+            *slot = Some(engine);
+            true
+        }
+        let (size, scale, waker) = (self.size, self.scale_factor, self.waker.as_ref());
+        let created = match kind {
+            #[cfg(feature = "litehtml")]
+            EngineKind::Litehtml => init(&mut self.litehtml, size, scale, waker),
+            #[cfg(feature = "blitz")]
+            EngineKind::Blitz => init(&mut self.blitz, size, scale, waker),
+            #[cfg(feature = "servo")]
+            EngineKind::Servo => init(&mut self.servo, size, scale, waker),
+            #[cfg(feature = "cef")]
+            EngineKind::Cef => init(&mut self.cef, size, scale, waker),
+        };
+        // Only the CPU engines take a fetch policy
+        #[cfg(any(feature = "litehtml", feature = "blitz"))]
+        if created {
+            if let Some((mode, retry)) = self.fetch_policy.clone() {
+                self.set_fetch_policy(mode, retry);
+            }
+        }
+        #[cfg(not(any(feature = "litehtml", feature = "blitz")))]
+        let _ = created;
+    }
+
+    /// The engine a page should open in, after the JavaScript fallback.
+    fn pick(&self, page: &PageType) -> EngineKind {
+        let kind = (self.router)(page);
+        match page {
+            PageType::Html(html) if self.js_fallback && !kind.has_javascript() => {
+                match EngineKind::javascript() {
+                    Some(js) if needs_javascript(html) => js,
+                    _ => kind,
+                }
+            }
+            _ => kind,
+        }
+    }
+
+    /// Create a view in the backend for `kind` and return its inner id.
+    fn create_inner(
+        &mut self,
+        kind: EngineKind,
+        size: Size<u32>,
+        content: Option<PageType>,
+    ) -> ViewId {
+        self.ensure_backend(kind);
+        let inner = match content {
+            // Mirror what the widgets do for engines that can't fetch:
+            // the view starts blank and remembers the URL to fetch.
+            Some(PageType::Url(url))
+                if !dispatch!(self.as_ref(kind), |e| e.handles_urls()).unwrap_or(true) =>
+            {
+                dispatch!(self.as_mut(kind), |e| {
+                    let inner = e.new_view(size, None);
+                    e.goto(inner, PageType::Url(url));
+                    inner
+                })
+            }
+            content => dispatch!(self.as_mut(kind), |e| e.new_view(size, content)),
+        };
+        inner.expect("backend was just created")
+    }
+
+    /// Move a view to another backend, keeping its outer id.
+    fn migrate(&mut self, id: ViewId, kind: EngineKind, content: PageType) {
+        let Some((old_kind, old_inner)) = self.route(id) else {
+            return;
+        };
+        // Keep events the old backend has not reported yet.
+        let pending = dispatch!(self.as_mut(old_kind), |e| e.take_events()).unwrap_or_default();
+        for event in pending {
+            if let Some(outer) = self.outer_id(old_kind, event.view) {
+                self.events.push(EngineEvent::new(outer, event.event));
+            }
+        }
+        dispatch!(self.as_mut(old_kind), |e| e.remove_view(old_inner));
+
+        let size = self
+            .size
+            .unwrap_or(Size::new(ImageInfo::WIDTH, ImageInfo::HEIGHT));
+        let inner = self.create_inner(kind, size, Some(content));
+        if let Some(view) = self.views.iter_mut().find(|v| v.id == id) {
+            view.kind = kind;
+            view.inner = inner;
+        }
+    }
+}
+
+impl Engine for AnyEngine {
+    fn update(&mut self) {
+        for_each_backend!(self.as_mut(), |e| e.update());
+    }
+
+    fn render(&mut self, size: Size<u32>) {
+        for_each_backend!(self.as_mut(), |e| e.render(size));
+    }
+
+    fn request_render(&mut self, id: ViewId, size: Size<u32>) {
+        if let Some((kind, inner)) = self.route(id) {
+            dispatch!(self.as_mut(kind), |e| e.request_render(inner, size));
+        }
+    }
+
+    fn new_view(&mut self, size: Size<u32>, content: Option<PageType>) -> ViewId {
+        let kind = match &content {
+            Some(page) => self.pick(page),
+            None => self.pick(&PageType::Html(String::new())),
+        };
+        let inner = self.create_inner(kind, size, content);
+        // Keep the backend's id unless another backend already handed it out.
+        let id = if self.find_view(inner).is_some() {
+            rand::thread_rng().gen()
+        } else {
+            inner
+        };
+        self.views.push(AnyView { id, kind, inner });
+        id
+    }
+
+    fn remove_view(&mut self, id: ViewId) {
+        if let Some((kind, inner)) = self.route(id) {
+            dispatch!(self.as_mut(kind), |e| e.remove_view(inner));
+        }
+        self.views.retain(|v| v.id != id);
+    }
+
+    fn has_view(&self, id: ViewId) -> bool {
+        self.find_view(id).is_some()
+    }
+
+    fn focus(&mut self) {
+        for_each_backend!(self.as_mut(), |e| e.focus());
+    }
+
+    fn unfocus(&self) {
+        for_each_backend!(self.as_ref(), |e| e.unfocus());
+    }
+
+    fn resize(&mut self, size: Size<u32>) {
+        self.size = Some(size);
+        for_each_backend!(self.as_mut(), |e| e.resize(size));
+    }
+
+    fn set_scale_factor(&mut self, scale: f32) {
+        self.scale_factor = scale;
+        for_each_backend!(self.as_mut(), |e| e.set_scale_factor(scale));
+    }
+
+    fn set_waker(&mut self, waker: Waker) {
+        for_each_backend!(self.as_mut(), |e| e.set_waker(waker.clone()));
+        self.waker = Some(waker);
+    }
+
+    #[cfg(any(feature = "litehtml", feature = "blitz"))]
+    fn set_fetch_policy(&mut self, mode: crate::FetchMode, retry: crate::RetryPolicy) {
+        for_each_backend!(self.as_mut(), |e| e
+            .set_fetch_policy(mode.clone(), retry.clone()));
+        self.fetch_policy = Some((mode, retry));
+    }
+
+    fn needs_update(&self) -> bool {
+        let mut needed = !self.events.is_empty();
+        for_each_backend!(self.as_ref(), |e| needed |= e.needs_update());
+        needed
+    }
+
+    /// Depends on the view; the widgets ask through [`Engine::view_handles_urls`].
+    fn handles_urls(&self) -> bool {
+        false
+    }
+
+    fn view_handles_urls(&self, id: ViewId) -> bool {
+        self.route(id)
+            .and_then(|(kind, inner)| dispatch!(self.as_ref(kind), |e| e.view_handles_urls(inner)))
+            .unwrap_or(self.handles_urls())
+    }
+
+    /// The union of what the backends created so far support, so it is
+    /// empty until the first view exists. Ask
+    /// [`Engine::view_capabilities`] about a particular view.
+    fn capabilities(&self) -> Capabilities {
+        let mut all = Capabilities::default();
+        for_each_backend!(self.as_ref(), |e| {
+            let c = e.capabilities();
+            all.javascript |= c.javascript;
+            all.history |= c.history;
+            all.keyboard_input |= c.keyboard_input;
+            all.text_selection |= c.text_selection;
+            all.native_urls |= c.native_urls;
+        });
+        all
+    }
+
+    fn view_capabilities(&self, id: ViewId) -> Capabilities {
+        self.route(id)
+            .and_then(|(kind, inner)| dispatch!(self.as_ref(kind), |e| e.view_capabilities(inner)))
+            .unwrap_or_default()
+    }
+
+    fn handle_keyboard_event(&mut self, id: ViewId, event: keyboard::Event) {
+        if let Some((kind, inner)) = self.route(id) {
+            dispatch!(self.as_mut(kind), |e| e.handle_keyboard_event(inner, event));
+        }
+    }
+
+    fn handle_mouse_event(&mut self, id: ViewId, point: Point, event: mouse::Event) {
+        if let Some((kind, inner)) = self.route(id) {
+            dispatch!(self.as_mut(kind), |e| e
+                .handle_mouse_event(inner, point, event));
+        }
+    }
+
+    fn scroll(&mut self, id: ViewId, delta: mouse::ScrollDelta) {
+        if let Some((kind, inner)) = self.route(id) {
+            dispatch!(self.as_mut(kind), |e| e.scroll(inner, delta));
+        }
+    }
+
+    fn goto(&mut self, id: ViewId, page_type: PageType) {
+        let Some((kind, inner)) = self.route(id) else {
+            return;
+        };
+        if let PageType::Html(html) = &page_type {
+            if let Some(js) = EngineKind::javascript() {
+                if self.js_fallback && !kind.has_javascript() && needs_javascript(html) {
+                    // Hand over the HTML already fetched rather than fetching
+                    // it again, which would bypass the widget's fetch mode.
+                    let url = self.get_url(id);
+                    let content = if url.starts_with("http://") || url.starts_with("https://") {
+                        PageType::Html(with_base(html, &url))
+                    } else {
+                        page_type
+                    };
+                    self.migrate(id, js, content);
+                    return;
+                }
+            }
+        }
+        dispatch!(self.as_mut(kind), |e| e.goto(inner, page_type));
+    }
+
+    fn refresh(&mut self, id: ViewId) {
+        if let Some((kind, inner)) = self.route(id) {
+            dispatch!(self.as_mut(kind), |e| e.refresh(inner));
+        }
+    }
+
+    fn go_forward(&mut self, id: ViewId) {
+        if let Some((kind, inner)) = self.route(id) {
+            dispatch!(self.as_mut(kind), |e| e.go_forward(inner));
+        }
+    }
+
+    fn go_back(&mut self, id: ViewId) {
+        if let Some((kind, inner)) = self.route(id) {
+            dispatch!(self.as_mut(kind), |e| e.go_back(inner));
+        }
+    }
+
+    fn can_go_back(&self, id: ViewId) -> bool {
+        self.route(id)
+            .and_then(|(kind, inner)| dispatch!(self.as_ref(kind), |e| e.can_go_back(inner)))
+            .unwrap_or(false)
+    }
+
+    fn can_go_forward(&self, id: ViewId) -> bool {
+        self.route(id)
+            .and_then(|(kind, inner)| dispatch!(self.as_ref(kind), |e| e.can_go_forward(inner)))
+            .unwrap_or(false)
+    }
+
+    fn is_loading(&self, id: ViewId) -> bool {
+        self.route(id)
+            .and_then(|(kind, inner)| dispatch!(self.as_ref(kind), |e| e.is_loading(inner)))
+            .unwrap_or(false)
+    }
+
+    fn evaluate_script(
+        &mut self,
+        id: ViewId,
+        script_id: ScriptId,
+        script: String,
+    ) -> Result<(), WebViewError> {
+        self.route(id)
+            .and_then(|(kind, inner)| {
+                dispatch!(self.as_mut(kind), |e| e
+                    .evaluate_script(inner, script_id, script))
+            })
+            .unwrap_or_else(|| Err(WebViewError::Script(format!("no view with id {id}"))))
+    }
+
+    fn get_url(&self, id: ViewId) -> String {
+        self.route(id)
+            .and_then(|(kind, inner)| dispatch!(self.as_ref(kind), |e| e.get_url(inner)))
+            .unwrap_or_else(|| "about:blank".to_string())
+    }
+
+    fn get_title(&self, id: ViewId) -> String {
+        self.route(id)
+            .and_then(|(kind, inner)| dispatch!(self.as_ref(kind), |e| e.get_title(inner)))
+            .unwrap_or_default()
+    }
+
+    fn get_cursor(&self, id: ViewId) -> Interaction {
+        self.route(id)
+            .and_then(|(kind, inner)| dispatch!(self.as_ref(kind), |e| e.get_cursor(inner)))
+            .unwrap_or(Interaction::Idle)
+    }
+
+    fn get_view(&self, id: ViewId) -> &ImageInfo {
+        static BLANK: std::sync::LazyLock<ImageInfo> = std::sync::LazyLock::new(ImageInfo::default);
+        self.route(id)
+            .and_then(|(kind, inner)| dispatch!(self.as_ref(kind), |e| e.get_view(inner)))
+            .unwrap_or(&BLANK)
+    }
+
+    fn get_tiles(&self, id: ViewId) -> &[Tile] {
+        self.route(id)
+            .and_then(|(kind, inner)| dispatch!(self.as_ref(kind), |e| e.get_tiles(inner)))
+            .unwrap_or(&[])
+    }
+
+    fn get_scroll_y(&self, id: ViewId) -> f32 {
+        self.route(id)
+            .and_then(|(kind, inner)| dispatch!(self.as_ref(kind), |e| e.get_scroll_y(inner)))
+            .unwrap_or(0.0)
+    }
+
+    fn get_content_height(&self, id: ViewId) -> f32 {
+        self.route(id)
+            .and_then(|(kind, inner)| dispatch!(self.as_ref(kind), |e| e.get_content_height(inner)))
+            .unwrap_or(0.0)
+    }
+
+    fn get_selected_text(&self, id: ViewId) -> Option<String> {
+        let (kind, inner) = self.route(id)?;
+        dispatch!(self.as_ref(kind), |e| e.get_selected_text(inner)).flatten()
+    }
+
+    fn get_selection_rects(&self, id: ViewId) -> &[[f32; 4]] {
+        self.route(id)
+            .and_then(|(kind, inner)| {
+                dispatch!(self.as_ref(kind), |e| e.get_selection_rects(inner))
+            })
+            .unwrap_or(&[])
+    }
+
+    fn take_anchor_click(&mut self, id: ViewId) -> Option<String> {
+        let (kind, inner) = self.route(id)?;
+        dispatch!(self.as_mut(kind), |e| e.take_anchor_click(inner)).flatten()
+    }
+
+    fn scroll_to_fragment(&mut self, id: ViewId, fragment: &str) -> bool {
+        let Some((kind, inner)) = self.route(id) else {
+            return false;
+        };
+        dispatch!(self.as_mut(kind), |e| e.scroll_to_fragment(inner, fragment)).unwrap_or(false)
+    }
+
+    fn take_pending_images(&mut self) -> Vec<(ViewId, String, String, bool)> {
+        let mut result = Vec::new();
+        for &kind in URL_PREFERENCE {
+            let pending = dispatch!(self.as_mut(kind), |e| e.take_pending_images());
+            for (inner, src, baseurl, redraw) in pending.unwrap_or_default() {
+                if let Some(id) = self.outer_id(kind, inner) {
+                    result.push((id, src, baseurl, redraw));
+                }
+            }
+        }
+        result
+    }
+
+    fn take_events(&mut self) -> Vec<EngineEvent> {
+        let mut result = std::mem::take(&mut self.events);
+        for &kind in URL_PREFERENCE {
+            let events = dispatch!(self.as_mut(kind), |e| e.take_events());
+            for event in events.unwrap_or_default() {
+                if let Some(id) = self.outer_id(kind, event.view) {
+                    result.push(EngineEvent::new(id, event.event));
+                }
+            }
+        }
+        result
+    }
+
+    fn set_css_cache(&mut self, id: ViewId, cache: HashMap<String, String>) {
+        if let Some((kind, inner)) = self.route(id) {
+            dispatch!(self.as_mut(kind), |e| e.set_css_cache(inner, cache));
+        }
+    }
+
+    fn load_image_from_bytes(
+        &mut self,
+        id: ViewId,
+        url: &str,
+        bytes: &[u8],
+        redraw_on_ready: bool,
+    ) {
+        if let Some((kind, inner)) = self.route(id) {
+            dispatch!(self.as_mut(kind), |e| {
+                e.load_image_from_bytes(inner, url, bytes, redraw_on_ready)
+            });
+        }
+    }
+
+    fn image_fetch_failed(&mut self, id: ViewId, url: &str) {
+        if let Some((kind, inner)) = self.route(id) {
+            dispatch!(self.as_mut(kind), |e| e.image_fetch_failed(inner, url));
+        }
+    }
+
+    fn flush_staged_images(&mut self, id: ViewId, size: Size<u32>) {
+        if let Some((kind, inner)) = self.route(id) {
+            dispatch!(self.as_mut(kind), |e| e.flush_staged_images(inner, size));
+        }
+    }
+
+    fn view_ids(&self) -> Vec<ViewId> {
+        self.views.iter().map(|v| v.id).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripts_that_run_need_javascript() {
+        assert!(needs_javascript("<p>hi</p><script>go()</script>"));
+        assert!(needs_javascript("<SCRIPT SRC=app.js></SCRIPT>"));
+        assert!(needs_javascript("<script type=module src=app.js></script>"));
+        assert!(needs_javascript(
+            "<script type='text/javascript'>go()</script>"
+        ));
+        assert!(needs_javascript("<script type=\" \">go()</script>"));
+        assert!(needs_javascript("<script async>go()</script>"));
+    }
+
+    #[test]
+    fn data_blocks_and_hidden_scripts_do_not() {
+        assert!(!needs_javascript(
+            "<script type=\"application/ld+json\">{\"@type\": \"Thing\"}</script>"
+        ));
+        assert!(!needs_javascript(
+            "<script type=text/template><p></p></script>"
+        ));
+        assert!(!needs_javascript("<!-- <script>go()</script> --><p>hi</p>"));
+        assert!(!needs_javascript("<![CDATA[<script>go()]]><p>hi</p>"));
+        assert!(!needs_javascript("<title><script>go()</script></title>"));
+        assert!(!needs_javascript("<div title=\"<script>\">hi</div>"));
+        assert!(!needs_javascript(
+            "<noscript><script>go()</script></noscript>"
+        ));
+        assert!(!needs_javascript("</script><p>hi</p>"));
+    }
+
+    #[test]
+    fn start_tags_skip_quoted_values_and_raw_text() {
+        let html =
+            "<!doctype html><a title=\"a>b\" href=x>link</a><style>p>b{}</style><p class='c'>";
+        let tags = start_tags(html);
+        let names: Vec<_> = tags.iter().map(|tag| tag.name).collect();
+        assert_eq!(names, ["a", "style", "p"]);
+        assert_eq!(
+            &html[tags[0].start..tags[0].end],
+            "<a title=\"a>b\" href=x>"
+        );
+        assert_eq!(&html[tags[2].start..tags[2].end], "<p class='c'>");
+    }
+
+    #[test]
+    fn start_tags_stop_at_unterminated_markup() {
+        assert_eq!(start_tags("<p>a<!-- never closed <b>").len(), 1);
+        assert_eq!(start_tags("<p>a<b title=\"never closed>").len(), 1);
+        assert_eq!(start_tags("1 < 2 <p>").len(), 1);
+    }
+
+    #[test]
+    fn attributes_with_any_quoting() {
+        let attrs = " type=module src='a b.js' data-x=\"it's\" defer/";
+        assert_eq!(attribute(attrs, "type"), Some("module"));
+        assert_eq!(attribute(attrs, "src"), Some("a b.js"));
+        assert_eq!(attribute(attrs, "data-x"), Some("it's"));
+        assert_eq!(attribute(attrs, "defer"), Some(""));
+        assert_eq!(attribute(attrs, "async"), None);
+        assert_eq!(attribute(" type = \"module\"", "type"), Some("module"));
+        assert_eq!(attribute(" data-type=module", "type"), None);
+    }
+
+    #[test]
+    fn base_goes_at_the_start_of_head() {
+        assert_eq!(
+            with_base(
+                "<HTML><HEAD><title>t</title></HEAD></HTML>",
+                "https://a.test/?x=1&y=\"2\""
+            ),
+            "<HTML><HEAD><base href=\"https://a.test/?x=1&amp;y=&quot;2&quot;\"><title>t</title></HEAD></HTML>"
+        );
+    }
+
+    #[test]
+    fn base_without_head() {
+        assert_eq!(
+            with_base("<html><body>hi</body></html>", "https://a.test/"),
+            "<html><base href=\"https://a.test/\"><body>hi</body></html>"
+        );
+        assert_eq!(
+            with_base("hi <p>there</p>", "https://a.test/"),
+            "hi <base href=\"https://a.test/\"><p>there</p>"
+        );
+        assert_eq!(
+            with_base("plain text", "https://a.test/"),
+            "<base href=\"https://a.test/\">plain text"
+        );
+    }
+
+    #[test]
+    fn existing_base_is_kept() {
+        let html = "<head><BASE HREF=\"https://b.test/\"></head><p>hi</p>";
+        assert_eq!(with_base(html, "https://a.test/"), html);
+        // A `<base>` inside a comment doesn't count
+        assert!(with_base("<!-- <base> --><head></head>", "https://a.test/")
+            .contains("<head><base href=\"https://a.test/\">"));
+    }
+
+    #[cfg(all(feature = "litehtml", not(any(feature = "servo", feature = "cef"))))]
+    #[test]
+    fn scripts_stay_put_without_a_javascript_engine() {
+        let mut engine = AnyEngine::with_router(|_| EngineKind::Litehtml);
+        let id = engine.new_view(
+            Size::new(200, 100),
+            Some(PageType::Html("<p>hi</p>".into())),
+        );
+        engine.goto(id, PageType::Html("<script>go()</script>".into()));
+        assert_eq!(engine.engine_kind(id), Some(EngineKind::Litehtml));
+        assert!(engine.has_view(id));
+    }
+
+    #[cfg(all(feature = "litehtml", feature = "servo"))]
+    #[test]
+    fn scripts_move_the_view_to_a_javascript_engine() {
+        let mut engine = AnyEngine::with_router(|_| EngineKind::Litehtml);
+        let id = engine.new_view(
+            Size::new(200, 100),
+            Some(PageType::Html("<p>hi</p>".into())),
+        );
+        let inner = engine.route(id).unwrap().1;
+        engine.goto(id, PageType::Html("<script>go()</script>".into()));
+
+        assert_eq!(engine.engine_kind(id), Some(EngineKind::Servo));
+        assert_eq!(engine.view_ids(), [id]);
+        let litehtml = engine.litehtml.as_ref().unwrap();
+        assert!(!litehtml.has_view(inner));
+        assert!(engine.view_capabilities(id).javascript);
+    }
+}
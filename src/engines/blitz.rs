@@ -1,13 +1,19 @@
-use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use iced::keyboard;
 use iced::mouse::{self, Interaction};
 use iced::{Point, Size};
 use rand::Rng;
 
-use super::{Engine, PageType, PixelFormat, ViewId};
-use crate::ImageInfo;
+use super::load::LoadTracker;
+use super::tiles::{HoverDamage, TileCache, HOVER_THROTTLE};
+use super::{Capabilities, Engine, PageType, ViewId};
+use crate::fetch::FetchOptions;
+use crate::{
+    EngineEvent, FetchMode, ImageInfo, RetryPolicy, Tile, Waker, WebViewError, WebViewEvent,
+};
 
 use anyrender::render_to_buffer;
 use anyrender_vello_cpu::VelloCpuImageRenderer;
@@ -20,12 +26,19 @@ use blitz_traits::events::{
     MouseEventButtons, PointerCoords, PointerDetails, UiEvent,
 };
 use blitz_traits::navigation::{NavigationOptions, NavigationProvider};
-use blitz_traits::net::NetProvider;
+use blitz_traits::net::{Bytes, Method, NetHandler, NetProvider, Request};
 use blitz_traits::shell::{ColorScheme, ShellProvider, Viewport};
 use cursor_icon::CursorIcon;
 use keyboard_types::Modifiers;
 use smol_str::SmolStr;
 
+/// Minimum time between two animation repaints (about 30 per second).
+const ANIMATION_THROTTLE: Duration = Duration::from_millis(33);
+
+/// Seconds the timeline jumps when animations are off; longer than any
+/// animation a page would run.
+const SKIP_ANIMATIONS: f64 = 86_400.0;
+
 /// Captures link clicks from the Blitz document.
 struct LinkCapture(Arc<Mutex<Option<String>>>);
 
@@ -35,6 +48,122 @@ impl NavigationProvider for LinkCapture {
     }
 }
 
+/// Sub-resource fetches of one document, shared with its net provider.
+#[derive(Default)]
+struct NetCounter {
+    started: AtomicUsize,
+    finished: AtomicUsize,
+    /// A fetch finished since the last `take_arrived`.
+    arrived: AtomicBool,
+    /// Failed fetches, reported as error events on the next update.
+    errors: Mutex<Vec<WebViewError>>,
+    /// Filled by `set_waker`; shared by every view of the engine.
+    waker: Arc<Mutex<Option<Waker>>>,
+}
+
+impl NetCounter {
+    /// Fetches still in flight.
+    fn pending(&self) -> usize {
+        let finished = self.finished.load(Ordering::Acquire);
+        self.started
+            .load(Ordering::Acquire)
+            .saturating_sub(finished)
+    }
+
+    /// Fraction of the fetches started so far that have finished.
+    fn progress(&self) -> f32 {
+        let started = self.started.load(Ordering::Acquire);
+        if started == 0 {
+            return 1.0;
+        }
+        self.finished.load(Ordering::Acquire) as f32 / started as f32
+    }
+
+    fn has_arrived(&self) -> bool {
+        self.arrived.load(Ordering::Acquire)
+    }
+
+    fn take_arrived(&self) -> bool {
+        self.arrived.swap(false, Ordering::AcqRel)
+    }
+
+    fn finish_one(&self) {
+        self.finished.fetch_add(1, Ordering::AcqRel);
+        self.arrived.store(true, Ordering::Release);
+        if let Some(waker) = self.waker.lock().unwrap().as_ref() {
+            waker.wake();
+        }
+    }
+}
+
+/// Wraps the real net provider to count fetches, so the engine resolves only
+/// when a sub-resource arrived and knows when the last one is done.
+struct CountingNet {
+    inner: Arc<dyn NetProvider>,
+    counter: Arc<NetCounter>,
+}
+
+impl NetProvider for CountingNet {
+    fn fetch(&self, doc_id: usize, request: Request, handler: Box<dyn NetHandler>) {
+        self.counter.started.fetch_add(1, Ordering::AcqRel);
+        let handler = CountedHandler {
+            inner: Some(handler),
+            counter: Arc::clone(&self.counter),
+        };
+        self.inner.fetch(doc_id, request, Box::new(handler));
+    }
+}
+
+/// Fetches `http`/`https` sub-resources through the crate's fetch layer, so
+/// Blitz shares litehtml's size limits, retries and record/replay. Other
+/// schemes (`data:`, `file:`) and non-GET requests go to blitz_net.
+struct FetchNet {
+    options: FetchOptions,
+    fallback: Arc<dyn NetProvider>,
+    counter: Arc<NetCounter>,
+}
+
+impl NetProvider for FetchNet {
+    fn fetch(&self, doc_id: usize, request: Request, handler: Box<dyn NetHandler>) {
+        if !matches!(request.url.scheme(), "http" | "https") || request.method != Method::GET {
+            self.fallback.fetch(doc_id, request, handler);
+            return;
+        }
+        let url = request.url.to_string();
+        let options = self.options.clone();
+        let counter = Arc::clone(&self.counter);
+        // Stylesheets and fonts are held to the image size limit too
+        crate::fetch::spawn(async move {
+            match crate::fetch::fetch_image(url.clone(), options).await {
+                Ok(bytes) => handler.bytes(url, Bytes::from(bytes)),
+                // Dropping the handler marks the fetch finished
+                Err(e) => counter.errors.lock().unwrap().push(e),
+            }
+        });
+    }
+}
+
+/// Marks its fetch finished when dropped: after the bytes were handed on,
+/// or unused when the request failed.
+struct CountedHandler {
+    inner: Option<Box<dyn NetHandler>>,
+    counter: Arc<NetCounter>,
+}
+
+impl NetHandler for CountedHandler {
+    fn bytes(mut self: Box<Self>, resolved_url: String, bytes: Bytes) {
+        if let Some(inner) = self.inner.take() {
+            inner.bytes(resolved_url, bytes);
+        }
+    }
+}
+
+impl Drop for CountedHandler {
+    fn drop(&mut self) {
+        self.counter.finish_one();
+    }
+}
+
 /// Shell provider that tracks cursor and redraw requests.
 struct WebviewShell {
     cursor: Arc<Mutex<CursorIcon>>,
@@ -50,23 +179,39 @@ struct BlitzView {
     id: ViewId,
     document: Option<HtmlDocument>,
     net_provider: Arc<dyn NetProvider>,
+    /// Fetches of the current document's net provider.
+    net: Arc<NetCounter>,
     nav_capture: Arc<Mutex<Option<String>>>,
     cursor_icon: Arc<Mutex<CursorIcon>>,
     url: String,
     title: String,
     cursor: Interaction,
+    /// Topmost visible tile, for `get_view`.
     last_frame: ImageInfo,
-    last_frame_hash: u64,
+    tiles: TileCache,
+    /// `:hover` changes not yet repainted.
+    hover: HoverDamage,
+    /// Animation frames not yet repainted, as the visible region.
+    motion: HoverDamage,
     needs_render: bool,
-    /// Number of update ticks to keep draining resources after goto().
-    /// blitz_net fetches sub-resources (images, CSS) asynchronously; we need
-    /// to call resolve() periodically to pick them up. Once the budget runs
-    /// out we stop polling (resolve is expensive for large documents).
-    resource_ticks: u32,
     scroll_y: f32,
     content_height: f32,
     size: Size<u32>,
     scale: f32,
+    /// Start of the document's animation timeline.
+    clock: Instant,
+    /// Seconds added to `clock` to skip animations to their end.
+    skipped: f64,
+    /// Run animations in real time; off skips each one to its end state.
+    animations: bool,
+    /// CSS animations or transitions are running, so every update queues a
+    /// repaint of the visible region.
+    animating: bool,
+    /// Load lifecycle of the current page. Finishes once `net` has no
+    /// fetches in flight.
+    load: LoadTracker,
+    /// Events not yet drained by `take_events`.
+    events: Vec<WebViewEvent>,
 }
 
 /// CPU-based HTML rendering engine backed by Blitz (Stylo + Taffy + Vello).
@@ -77,6 +222,10 @@ pub struct Blitz {
     views: Vec<BlitzView>,
     scale_factor: f32,
     color_scheme: ColorScheme,
+    hover_throttle: Option<Duration>,
+    animations: bool,
+    waker: Arc<Mutex<Option<Waker>>>,
+    fetch: FetchOptions,
 }
 
 fn detect_color_scheme() -> ColorScheme {
@@ -100,11 +249,32 @@ impl Default for Blitz {
             views: Vec::new(),
             scale_factor: 1.0,
             color_scheme: detect_color_scheme(),
+            hover_throttle: Some(HOVER_THROTTLE),
+            animations: true,
+            waker: Arc::default(),
+            fetch: FetchOptions::default(),
         }
     }
 }
 
 impl Blitz {
+    /// Repaint `:hover` styles by redrawing only the elements whose hover
+    /// state changed, at most once per `throttle`. On by default with a
+    /// 50 ms throttle; `None` leaves hover styles unrendered.
+    pub fn hover_repaint(mut self, throttle: Option<Duration>) -> Self {
+        self.hover_throttle = throttle;
+        self
+    }
+
+    /// Run CSS animations and transitions (on by default). Turn off for
+    /// users who prefer reduced motion: each animation or transition then
+    /// jumps to its end state as soon as it starts, looping ones stop
+    /// wherever that lands, and nothing ticks once loading is done.
+    pub fn animations(mut self, enabled: bool) -> Self {
+        self.animations = enabled;
+        self
+    }
+
     fn find_view(&self, id: ViewId) -> Option<&BlitzView> {
         self.views.iter().find(|v| v.id == id)
     }
@@ -131,9 +301,26 @@ fn cursor_icon_to_interaction(icon: CursorIcon) -> Interaction {
     }
 }
 
-/// Create a new net provider for sub-resource fetching.
-fn new_net_provider() -> Arc<dyn NetProvider> {
-    Provider::shared(None)
+/// Create a new net provider for sub-resource fetching, counting its
+/// fetches in the returned `NetCounter`.
+fn new_net_provider(
+    options: &FetchOptions,
+    waker: &Arc<Mutex<Option<Waker>>>,
+) -> (Arc<dyn NetProvider>, Arc<NetCounter>) {
+    let counter = Arc::new(NetCounter {
+        waker: Arc::clone(waker),
+        ..NetCounter::default()
+    });
+    let fetch = FetchNet {
+        options: options.clone(),
+        fallback: Provider::shared(None),
+        counter: Arc::clone(&counter),
+    };
+    let net = CountingNet {
+        inner: Arc::new(fetch),
+        counter: Arc::clone(&counter),
+    };
+    (Arc::new(net), counter)
 }
 
 /// Parse HTML into a Blitz document with the given configuration.
@@ -168,17 +355,54 @@ fn create_document(
     doc
 }
 
-/// Max render height in logical pixels. Prevents multi-hundred-MB pixel
-/// buffers for very tall documents (e.g. docs.rs pages). Content beyond
-/// this height is reachable via scrolling but not pre-rasterized.
-const MAX_RENDER_HEIGHT: f32 = 8192.0;
+/// Restyle and lay out the document at the current animation time, and
+/// note whether animations are still running.
+///
+/// With animations off, the timeline is pushed a day past anything that
+/// started, so documents show where their animations end rather than
+/// where they begin.
+fn resolve_view(view: &mut BlitzView) {
+    let Some(doc) = view.document.as_mut() else {
+        view.animating = false;
+        return;
+    };
+    let now = view.clock.elapsed().as_secs_f64() + view.skipped;
+    doc.resolve(now);
+    if !view.animations && doc.is_animating() {
+        view.skipped += SKIP_ANIMATIONS;
+        doc.resolve(now + SKIP_ANIMATIONS);
+    }
+    view.animating = view.animations && doc.is_animating();
+}
+
+/// Text of the document's `<title>` element, empty if there is none.
+fn document_title(doc: &HtmlDocument) -> String {
+    doc.query_selector("title")
+        .ok()
+        .flatten()
+        .and_then(|id| doc.get_node(id))
+        .map(|node| node.text_content().trim().to_string())
+        .unwrap_or_default()
+}
 
-/// Render the document to an RGBA pixel buffer.
+/// Update the cached title and queue a `TitleChanged` event if it differs.
+fn update_title(view: &mut BlitzView) {
+    let title = view
+        .document
+        .as_ref()
+        .map(document_title)
+        .unwrap_or_default();
+    if title != view.title {
+        view.title = title.clone();
+        view.events.push(WebViewEvent::TitleChanged(title));
+    }
+}
+
+/// Rasterize the tiles around the scroll position.
 ///
-/// The buffer height is capped at `MAX_RENDER_HEIGHT` logical pixels to
-/// keep memory and CPU usage bounded. The widget layer uses `content_height`
-/// / `scroll_y` for scroll calculations; `content_height` is clamped to the
-/// rendered height so the scrollbar range matches what's actually rasterized.
+/// Only the bands of the document near the viewport are painted, so memory
+/// stays bounded however tall the page is. `needs_render` marks the cached
+/// tiles stale; scrolling only paints tiles that aren't cached yet.
 fn render_view(view: &mut BlitzView) {
     let w = view.size.width;
     let h = view.size.height;
@@ -187,67 +411,157 @@ fn render_view(view: &mut BlitzView) {
         return;
     }
 
-    let doc = match view.document.as_ref() {
+    let doc = match view.document.as_mut() {
         Some(d) => d,
         None => {
             view.last_frame = ImageInfo::blank(w, h);
+            view.tiles.clear();
             view.needs_render = false;
             return;
         }
     };
 
     let root_height = doc.root_element().final_layout.size.height;
-    let capped_height = root_height.min(MAX_RENDER_HEIGHT);
-    view.content_height = capped_height;
+    if root_height != view.content_height {
+        view.content_height = root_height;
+        view.events.push(WebViewEvent::ContentSizeChanged(Size::new(
+            w as f32,
+            root_height,
+        )));
+    }
 
     let scale = view.scale as f64;
     let render_w = (w as f64 * scale) as u32;
-    let render_h = ((capped_height as f64).max(h as f64) * scale) as u32;
-
-    if render_w == 0 || render_h == 0 {
+    if render_w == 0 {
         view.last_frame = ImageInfo::blank(w, h);
         view.needs_render = false;
         return;
     }
 
-    let buffer = render_to_buffer::<VelloCpuImageRenderer, _>(
-        |scene| {
-            paint_scene(scene, doc, scale, render_w, render_h, 0, 0);
-        },
-        render_w,
-        render_h,
-    );
-
-    let mut hasher = DefaultHasher::new();
-    buffer.hash(&mut hasher);
-    let new_hash = hasher.finish();
-
-    if view.last_frame.image_width() == render_w
-        && view.last_frame.image_height() == render_h
-        && view.last_frame_hash == new_hash
-    {
-        view.needs_render = false;
-        return;
+    if view.needs_render {
+        view.tiles.invalidate();
     }
+    // Paint each band by scrolling the document's viewport to its top.
+    let saved_scroll = doc.viewport_scroll();
+    view.tiles
+        .update(view.scroll_y, h as f32, root_height, |top, height| {
+            let mut scroll = saved_scroll;
+            scroll.y = top as f64;
+            doc.set_viewport_scroll(scroll);
+            let doc: &HtmlDocument = doc;
+            let render_h = ((height as f64 * scale).ceil() as u32).max(1);
+            let buffer = render_to_buffer::<VelloCpuImageRenderer, _>(
+                |scene| {
+                    paint_scene(scene, doc, scale, render_w, render_h, 0, 0);
+                },
+                render_w,
+                render_h,
+            );
+            (buffer, render_w, render_h)
+        });
+    doc.set_viewport_scroll(saved_scroll);
 
-    view.last_frame = ImageInfo::new(buffer, PixelFormat::Rgba, render_w, render_h);
-    view.last_frame_hash = new_hash;
+    if let Some(tile) = view.tiles.visible().first() {
+        view.last_frame = tile.image().clone();
+    }
     view.needs_render = false;
 }
 
-/// How many update ticks to keep draining resources after goto().
-/// At 10ms per tick this gives ~30s for sub-resources to arrive.
-const RESOURCE_TICK_BUDGET: u32 = 3000;
+/// Bounds of a node and everything below it, as `[x, y, width, height]` in
+/// logical document coordinates. Covers descendants that overflow the node,
+/// such as a dropdown opened by `:hover`.
+fn subtree_bounds(doc: &HtmlDocument, id: usize) -> Option<[f32; 4]> {
+    let mut bounds: Option<[f32; 4]> = None;
+    let mut stack = vec![id];
+    while let Some(id) = stack.pop() {
+        let Some(node) = doc.get_node(id) else {
+            continue;
+        };
+        let pos = node.absolute_position(0.0, 0.0);
+        let size = node.final_layout.size;
+        let [x0, y0, x1, y1] = bounds.get_or_insert([pos.x, pos.y, pos.x, pos.y]);
+        *x0 = x0.min(pos.x);
+        *y0 = y0.min(pos.y);
+        *x1 = x1.max(pos.x + size.width);
+        *y1 = y1.max(pos.y + size.height);
+        stack.extend(node.children.iter().copied());
+    }
+    bounds.map(|[x0, y0, x1, y1]| [x0, y0, x1 - x0, y1 - y0])
+}
 
-/// How often (in ticks) to actually call resolve() during the drain phase.
-/// resolve() is expensive (full Stylo + Taffy layout pass), so we throttle it.
-/// At ~10ms per tick, 100 ticks ≈ 1 second between resolve calls.
-const RESOLVE_INTERVAL: u32 = 100;
+/// Restyle after `:hover` changes and patch the affected elements into the
+/// cached tiles, at most once per `throttle`.
+fn repaint_hover(view: &mut BlitzView, throttle: Duration) {
+    if view.needs_render {
+        // A full redraw is coming anyway
+        view.hover.clear();
+        return;
+    }
+    if let Some(rects) = view.hover.take_due(throttle) {
+        repaint(view, rects, true);
+    }
+}
 
-/// Drain completed resource fetches and re-resolve the document.
-fn drain_and_resolve(view: &mut BlitzView) {
-    if let Some(ref mut doc) = view.document {
-        doc.resolve(0.0);
+/// Advance running animations and patch the visible region into the cached
+/// tiles, at most once per [`ANIMATION_THROTTLE`]. Tiles whose pixels did
+/// not change keep their image, so a blinking caret only damages the tile
+/// it is in, and only when it toggles.
+fn repaint_motion(view: &mut BlitzView) {
+    if view.needs_render {
+        view.motion.clear();
+        return;
+    }
+    if let Some(rects) = view.motion.take_due(ANIMATION_THROTTLE) {
+        repaint(view, rects, false);
+    }
+}
+
+/// Restyle and patch `rects` into the cached tiles, plus the hovered
+/// element if `hover` is set.
+///
+/// Falls back to a full redraw when the restyle changed the document height.
+fn repaint(view: &mut BlitzView, mut rects: Vec<[f32; 4]>, hover: bool) {
+    resolve_view(view);
+    let Some(doc) = view.document.as_mut() else {
+        return;
+    };
+
+    if doc.root_element().final_layout.size.height != view.content_height {
+        view.needs_render = true;
+        return;
+    }
+    if hover {
+        rects.extend(
+            doc.get_hover_node_id()
+                .and_then(|id| subtree_bounds(doc, id)),
+        );
+    }
+
+    let scale = view.scale as f64;
+    let saved_scroll = doc.viewport_scroll();
+    for rect in rects {
+        view.tiles.patch(rect, |x, y, width, height| {
+            let mut scroll = saved_scroll;
+            scroll.x = x as f64;
+            scroll.y = y as f64;
+            doc.set_viewport_scroll(scroll);
+            let doc: &HtmlDocument = doc;
+            let render_w = ((width as f64 * scale).ceil() as u32).max(1);
+            let render_h = ((height as f64 * scale).ceil() as u32).max(1);
+            let buffer = render_to_buffer::<VelloCpuImageRenderer, _>(
+                |scene| {
+                    paint_scene(scene, doc, scale, render_w, render_h, 0, 0);
+                },
+                render_w,
+                render_h,
+            );
+            (buffer, render_w, render_h)
+        });
+    }
+    doc.set_viewport_scroll(saved_scroll);
+
+    if let Some(tile) = view.tiles.visible().first() {
+        view.last_frame = tile.image().clone();
     }
 }
 
@@ -262,33 +576,81 @@ impl Engine for Blitz {
         false
     }
 
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            keyboard_input: true,
+            ..Capabilities::default()
+        }
+    }
+
     fn update(&mut self) {
         for view in &mut self.views {
-            if view.resource_ticks > 0 {
-                view.resource_ticks -= 1;
-                if view.resource_ticks % RESOLVE_INTERVAL == 0 {
-                    drain_and_resolve(view);
-                    view.needs_render = true;
-                }
+            // Resolving picks up the sub-resources that arrived since the
+            // last update, and may start fetching new ones.
+            let arrived = view.net.take_arrived();
+            if arrived {
+                resolve_view(view);
+                view.needs_render = true;
+            } else if view.animating {
+                // Resolved when the repaint is due
+                let (w, h) = (view.size.width as f32, view.size.height as f32);
+                view.motion.add(Some([0.0, view.scroll_y, w, h]));
+            }
+            if arrived {
+                view.load
+                    .subresources(view.net.progress(), &mut view.events);
+            }
+            let errors = std::mem::take(&mut *view.net.errors.lock().unwrap());
+            view.events
+                .extend(errors.into_iter().map(WebViewEvent::Error));
+            if view.load.is_dom_ready() && view.net.pending() == 0 {
+                view.load.finish(&mut view.events);
             }
         }
     }
 
+    fn set_waker(&mut self, waker: Waker) {
+        *self.waker.lock().unwrap() = Some(waker);
+    }
+
+    fn set_fetch_policy(&mut self, mode: FetchMode, retry: RetryPolicy) {
+        self.fetch = FetchOptions { mode, retry };
+    }
+
+    fn needs_update(&self) -> bool {
+        // Finished fetches wake us; tick to pick them up, to finish the
+        // load, and every frame while animating.
+        self.views.iter().any(|view| {
+            view.net.has_arrived()
+                || (view.load.is_dom_ready() && view.net.pending() == 0)
+                || view.needs_render
+                || view.animating
+                || view.hover.is_pending()
+                || view.motion.is_pending()
+        })
+    }
+
     fn render(&mut self, _size: Size<u32>) {
         for view in &mut self.views {
-            if view.needs_render {
-                render_view(view);
+            if let Some(throttle) = self.hover_throttle {
+                repaint_hover(view, throttle);
             }
+            repaint_motion(view);
+            render_view(view);
         }
     }
 
     fn request_render(&mut self, id: ViewId, _size: Size<u32>) {
+        let hover_throttle = self.hover_throttle;
         let Some(view) = self.find_view_mut(id) else {
             return;
         };
-        if view.needs_render {
-            render_view(view);
+        if let Some(throttle) = hover_throttle {
+            repaint_hover(view, throttle);
         }
+        repaint_motion(view);
+        // Also draws tiles scrolled into view since the last call
+        render_view(view);
     }
 
     fn new_view(&mut self, size: Size<u32>, content: Option<PageType>) -> ViewId {
@@ -299,7 +661,7 @@ impl Engine for Blitz {
 
         let nav_capture = Arc::new(Mutex::new(None));
         let cursor_icon = Arc::new(Mutex::new(CursorIcon::Default));
-        let net = new_net_provider();
+        let (net, counter) = new_net_provider(&self.fetch, &self.waker);
         let nav = Arc::new(LinkCapture(Arc::clone(&nav_capture)));
         let shell = Arc::new(WebviewShell {
             cursor: Arc::clone(&cursor_icon),
@@ -326,30 +688,50 @@ impl Engine for Blitz {
             None
         };
         let has_document = document.is_some();
+        let mut load = LoadTracker::default();
+        let mut events = Vec::new();
+        if !url.is_empty() {
+            events.push(WebViewEvent::UrlChanged(url.clone()));
+            load.start(&mut events);
+        }
+        if has_document {
+            // create_document parses and resolves synchronously
+            load.commit(&mut events);
+            load.dom_ready(&mut events);
+        }
 
         let mut view = BlitzView {
             id,
             document,
             net_provider: net,
+            net: counter,
             nav_capture,
             cursor_icon,
             url,
             title: String::new(),
             cursor: Interaction::Idle,
             last_frame: ImageInfo::blank(w, h),
-            last_frame_hash: 0,
+            tiles: TileCache::default(),
+            hover: HoverDamage::default(),
+            motion: HoverDamage::default(),
             needs_render: true,
-            resource_ticks: if has_document {
-                RESOURCE_TICK_BUDGET
-            } else {
-                0
-            },
             scroll_y: 0.0,
             content_height: 0.0,
             size,
             scale: self.scale_factor,
+            clock: Instant::now(),
+            skipped: 0.0,
+            animations: self.animations,
+            animating: self.animations && has_document,
+            load,
+            events,
         };
 
+        if !view.animations {
+            // Skip what the first resolve started to its end state
+            resolve_view(&mut view);
+        }
+        update_title(&mut view);
         render_view(&mut view);
         self.views.push(view);
         id
@@ -380,9 +762,8 @@ impl Engine for Blitz {
                 let phys_h = (size.height as f32 * scale) as u32;
                 let mut vp = doc.viewport_mut();
                 vp.window_size = (phys_w, phys_h);
-                drop(vp);
-                doc.resolve(0.0);
             }
+            resolve_view(view);
             view.needs_render = true;
         }
     }
@@ -400,9 +781,8 @@ impl Engine for Blitz {
                 let mut vp = doc.viewport_mut();
                 vp.window_size = (phys_w, phys_h);
                 vp.set_hidpi_scale(scale);
-                drop(vp);
-                doc.resolve(0.0);
             }
+            resolve_view(view);
             view.needs_render = true;
         }
     }
@@ -419,6 +799,9 @@ impl Engine for Blitz {
                     UiEvent::KeyUp(ke)
                 };
                 doc.handle_ui_event(ui_event);
+                // May have started a transition or caret blink; the next
+                // update resolves and finds out.
+                view.animating = view.animations;
             }
         }
     }
@@ -462,20 +845,32 @@ impl Engine for Blitz {
                         mods: Modifiers::empty(),
                         details: PointerDetails::default(),
                     }));
+                    view.animating = view.animations;
                 }
             }
             mouse::Event::CursorMoved { .. } => {
+                let hover_repaint = self.hover_throttle.is_some();
                 let Some(view) = self.find_view_mut(id) else {
                     return;
                 };
                 if let Some(ref mut doc) = view.document {
                     let doc_y = point.y + view.scroll_y;
-                    doc.set_hover_to(point.x, doc_y);
+                    let before = doc.get_hover_node_id();
+                    if doc.set_hover_to(point.x, doc_y) && hover_repaint {
+                        // Repaint where the old target was; the new one is
+                        // added once it has been restyled.
+                        view.hover
+                            .add(before.and_then(|id| subtree_bounds(doc, id)));
+                    }
                 }
                 let doc_cursor = view.document.as_ref().and_then(|d| d.get_cursor());
                 let shell_cursor = *view.cursor_icon.lock().unwrap();
                 let icon = doc_cursor.unwrap_or(shell_cursor);
-                view.cursor = cursor_icon_to_interaction(icon);
+                let cursor = cursor_icon_to_interaction(icon);
+                if cursor != view.cursor {
+                    view.cursor = cursor;
+                    view.events.push(WebViewEvent::CursorChanged(cursor));
+                }
             }
             mouse::Event::ButtonReleased(btn) => {
                 let button = match btn {
@@ -507,11 +902,16 @@ impl Engine for Blitz {
                         mods: Modifiers::empty(),
                         details: PointerDetails::default(),
                     }));
+                    view.animating = view.animations;
                 }
             }
             mouse::Event::CursorLeft => {
                 if let Some(view) = self.find_view_mut(id) {
-                    view.cursor = Interaction::Idle;
+                    if view.cursor != Interaction::Idle {
+                        view.cursor = Interaction::Idle;
+                        view.events
+                            .push(WebViewEvent::CursorChanged(Interaction::Idle));
+                    }
                 }
             }
             _ => {}
@@ -536,6 +936,8 @@ impl Engine for Blitz {
 
     fn goto(&mut self, id: ViewId, page_type: PageType) {
         let color_scheme = self.color_scheme;
+        let waker = Arc::clone(&self.waker);
+        let fetch = self.fetch.clone();
         let Some(view) = self.find_view_mut(id) else {
             return;
         };
@@ -545,8 +947,9 @@ impl Engine for Blitz {
                 let shell = Arc::new(WebviewShell {
                     cursor: Arc::clone(&view.cursor_icon),
                 });
-                let net = new_net_provider();
+                let (net, counter) = new_net_provider(&fetch, &waker);
                 view.net_provider = Arc::clone(&net);
+                view.net = counter;
 
                 view.document = Some(create_document(
                     &html,
@@ -560,10 +963,22 @@ impl Engine for Blitz {
                 ));
                 view.scroll_y = 0.0;
                 view.needs_render = true;
-                view.resource_ticks = RESOURCE_TICK_BUDGET;
+                view.clock = Instant::now();
+                view.skipped = 0.0;
+                view.animating = view.animations;
+                if !view.animations {
+                    resolve_view(view);
+                }
+                view.load.commit(&mut view.events);
+                view.load.dom_ready(&mut view.events);
+                update_title(view);
             }
             PageType::Url(url) => {
-                view.url = url;
+                if url != view.url {
+                    view.url = url.clone();
+                    view.events.push(WebViewEvent::UrlChanged(url));
+                }
+                view.load.start(&mut view.events);
             }
         }
     }
@@ -572,9 +987,7 @@ impl Engine for Blitz {
         let Some(view) = self.find_view_mut(id) else {
             return;
         };
-        if let Some(ref mut doc) = view.document {
-            doc.resolve(0.0);
-        }
+        resolve_view(view);
         view.needs_render = true;
     }
 
@@ -582,6 +995,10 @@ impl Engine for Blitz {
 
     fn go_back(&mut self, _id: ViewId) {}
 
+    fn is_loading(&self, id: ViewId) -> bool {
+        self.find_view(id).is_some_and(|v| v.load.is_loading())
+    }
+
     fn get_url(&self, id: ViewId) -> String {
         let Some(view) = self.find_view(id) else {
             return "about:blank".to_string();
@@ -610,6 +1027,10 @@ impl Engine for Blitz {
         self.find_view(id).map(|v| &v.last_frame).unwrap_or(&BLANK)
     }
 
+    fn get_tiles(&self, id: ViewId) -> &[Tile] {
+        self.find_view(id).map(|v| v.tiles.visible()).unwrap_or(&[])
+    }
+
     fn get_scroll_y(&self, id: ViewId) -> f32 {
         self.find_view(id).map(|v| v.scroll_y).unwrap_or(0.0)
     }
@@ -650,6 +1071,14 @@ impl Engine for Blitz {
     fn take_anchor_click(&mut self, id: ViewId) -> Option<String> {
         self.find_view_mut(id)?.nav_capture.lock().unwrap().take()
     }
+
+    fn take_events(&mut self) -> Vec<EngineEvent> {
+        let mut result = Vec::new();
+        for view in &mut self.views {
+            result.extend(view.events.drain(..).map(|e| EngineEvent::new(view.id, e)));
+        }
+        result
+    }
 }
 
 fn iced_keyboard_to_blitz(event: keyboard::Event) -> Option<BlitzKeyEvent> {
@@ -1,14 +1,19 @@
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::os::raw::c_int;
 use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use iced::keyboard;
 use iced::mouse::{self, Interaction};
-use iced::{Point, Size};
+use iced::{Point, Rectangle, Size};
 use rand::Rng;
 
-use super::{Engine, PageType, PixelFormat, ViewId};
-use crate::ImageInfo;
+use super::load::LoadTracker;
+use super::{Capabilities, Engine, PageType, PixelFormat, ViewId};
+use crate::{EngineEvent, ImageInfo, ScriptId, ScriptValue, Waker, WebViewError, WebViewEvent};
 
 // Pull in all CEF types, traits, and macros. The wrap_*! macros reference
 // ImplClient, WrapClient, Client, etc. by unqualified name, so a glob
@@ -19,20 +24,185 @@ use cef::*;
 /// Shared mutable state populated by CEF handler callbacks and drained
 /// each `update()` tick.
 struct SharedState {
-    frame_buffer: Option<(Vec<u8>, u32, u32)>,
+    /// The view as CEF last painted it.
+    canvas: Canvas,
+    /// `canvas` holds a paint not drained by `update()` yet.
+    painted: bool,
+    /// Regions repainted since the last frame drained by `update()`;
+    /// `None` when the whole frame has to be uploaded.
+    damage: Option<Vec<Rectangle<u32>>>,
+    /// The open popup widget, e.g. a `<select>` dropdown.
+    popup: Option<Popup>,
+    /// The popup was shown, moved, repainted or hidden since the last
+    /// `update()`.
+    popup_changed: bool,
     url: Option<String>,
     title: Option<String>,
     cursor_type: CursorType,
     size: Size<u32>,
     scale_factor: f32,
+    load: LoadTracker,
+    /// Scripts sent over DevTools and not answered yet, by DevTools message
+    /// id, with the caller's id and when they were sent.
+    scripts: HashMap<c_int, (ScriptId, Instant)>,
+    /// DevTools message id for the next script.
+    next_script: c_int,
+    /// Events not yet drained by `take_events`.
+    events: Vec<WebViewEvent>,
+}
+
+/// How long a script may run, including an awaited promise, before it is
+/// reported as failed.
+const SCRIPT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A persistent BGRA buffer the view's paints are copied into. A paint
+/// copies only its dirty rects, and the whole view only when the size
+/// changed or CEF reported no damage.
+#[derive(Default)]
+struct Canvas {
+    pixels: Vec<u8>,
+    width: u32,
+    height: u32,
+    /// Regions still holding an older paint, as in a buffer taken back from
+    /// a frame no longer shown; `None` when all of it does.
+    stale: Option<Vec<Rectangle<u32>>>,
+}
+
+impl Canvas {
+    /// Copy a `width`×`height` paint from CEF's `buffer`, which always holds
+    /// the whole view: `dirty` and the stale regions if the size matches,
+    /// otherwise everything.
+    fn paint(&mut self, buffer: &[u8], width: u32, height: u32, dirty: Option<&[Rectangle<u32>]>) {
+        let stale = self.stale.replace(Vec::new());
+        let rects = match (stale, dirty) {
+            (Some(mut stale), Some(dirty)) if (self.width, self.height) == (width, height) => {
+                stale.extend_from_slice(dirty);
+                stale
+            }
+            _ => {
+                self.pixels.clear();
+                self.pixels.extend_from_slice(buffer);
+                (self.width, self.height) = (width, height);
+                return;
+            }
+        };
+        let stride = 4 * width as usize;
+        for rect in rects {
+            let x = rect.x.min(width) as usize;
+            let right = rect.x.saturating_add(rect.width).min(width) as usize;
+            let bottom = rect.y.saturating_add(rect.height).min(height) as usize;
+            for y in rect.y.min(height) as usize..bottom {
+                let row = y * stride;
+                self.pixels[row + 4 * x..row + 4 * right]
+                    .copy_from_slice(&buffer[row + 4 * x..row + 4 * right]);
+            }
+        }
+    }
+}
+
+/// A popup widget CEF paints separately from the view.
+#[derive(Default)]
+struct Popup {
+    /// Where the popup is drawn, in view coordinates, moved inside the view
+    /// when CEF places it partly outside.
+    rect: Rectangle<c_int>,
+    /// Offset from `rect` to where CEF placed the popup.
+    offset: (c_int, c_int),
+    /// Last popup paint: BGRA pixels with their physical size.
+    pixels: Option<(Vec<u8>, u32, u32)>,
+}
+
+impl Popup {
+    /// Place the popup where CEF asked, moved inside a view of `size` like
+    /// CEF's own OSR sample does.
+    fn place(&mut self, requested: Rectangle<c_int>, size: Size<u32>) {
+        let placed = Rectangle {
+            x: requested
+                .x
+                .min(size.width as c_int - requested.width)
+                .max(0),
+            y: requested
+                .y
+                .min(size.height as c_int - requested.height)
+                .max(0),
+            width: requested.width,
+            height: requested.height,
+        };
+        self.offset = (requested.x - placed.x, requested.y - placed.y);
+        self.rect = placed;
+    }
+
+    /// Where CEF asked for the popup to go.
+    fn requested(&self) -> Rectangle<c_int> {
+        Rectangle {
+            x: self.rect.x + self.offset.0,
+            y: self.rect.y + self.offset.1,
+            ..self.rect
+        }
+    }
+
+    /// Translate a view point into the popup's own placement when it lies
+    /// over the popup, so input reaches the item under the cursor.
+    fn translate(&self, x: c_int, y: c_int) -> (c_int, c_int) {
+        let rect = &self.rect;
+        let inside =
+            x >= rect.x && x < rect.x + rect.width && y >= rect.y && y < rect.y + rect.height;
+        if inside {
+            (x + self.offset.0, y + self.offset.1)
+        } else {
+            (x, y)
+        }
+    }
+}
+
+/// A view point in browser coordinates, adjusted for a popup CEF placed
+/// partly outside the view.
+fn popup_point(shared: &SharedState, point: Point) -> (c_int, c_int) {
+    let (x, y) = (point.x as c_int, point.y as c_int);
+    match &shared.popup {
+        Some(popup) => popup.translate(x, y),
+        None => (x, y),
+    }
+}
+
+/// Pump requests from CEF's `on_schedule_message_pump_work`, which may
+/// arrive on any thread.
+#[derive(Default)]
+struct MessagePump {
+    scheduled: AtomicBool,
+    waker: Mutex<Option<Waker>>,
 }
 
 // -- CEF App handler --
 
+wrap_browser_process_handler! {
+    struct OsrBrowserProcessHandler {
+        pump: Arc<MessagePump>,
+    }
+
+    impl BrowserProcessHandler {
+        // With `external_message_pump` CEF asks for `do_message_loop_work`
+        // here; wake the widget instead of polling on a timer. Delayed
+        // requests are pumped early, which CEF handles by rescheduling.
+        fn on_schedule_message_pump_work(&self, _delay_ms: i64) {
+            self.pump.scheduled.store(true, Ordering::Release);
+            if let Some(waker) = self.pump.waker.lock().unwrap().as_ref() {
+                waker.wake();
+            }
+        }
+    }
+}
+
 wrap_app! {
-    struct OsrApp;
+    struct OsrApp {
+        pump: Arc<MessagePump>,
+    }
 
     impl App {
+        fn browser_process_handler(&self) -> Option<BrowserProcessHandler> {
+            Some(OsrBrowserProcessHandler::new(Arc::clone(&self.pump)))
+        }
+
         fn on_before_command_line_processing(
             &self,
             _process_type: Option<&CefString>,
@@ -91,11 +261,40 @@ wrap_render_handler! {
             0
         }
 
+        fn on_popup_show(&self, _browser: Option<&mut Browser>, show: c_int) {
+            let mut shared = self.shared.borrow_mut();
+            if show != 0 {
+                shared.popup.get_or_insert_with(Popup::default);
+            } else {
+                shared.popup = None;
+            }
+            shared.popup_changed = true;
+        }
+
+        fn on_popup_size(&self, _browser: Option<&mut Browser>, rect: Option<&Rect>) {
+            let Some(rect) = rect else {
+                return;
+            };
+            let mut shared = self.shared.borrow_mut();
+            let size = shared.size;
+            let requested = Rectangle {
+                x: rect.x,
+                y: rect.y,
+                width: rect.width,
+                height: rect.height,
+            };
+            shared
+                .popup
+                .get_or_insert_with(Popup::default)
+                .place(requested, size);
+            shared.popup_changed = true;
+        }
+
         fn on_paint(
             &self,
             _browser: Option<&mut Browser>,
-            _type_: PaintElementType,
-            _dirty_rects: Option<&[Rect]>,
+            type_: PaintElementType,
+            dirty_rects: Option<&[Rect]>,
             buffer: *const u8,
             width: c_int,
             height: c_int,
@@ -103,8 +302,43 @@ wrap_render_handler! {
             let w = width as usize;
             let h = height as usize;
             let len = w * h * 4;
-            let pixels = unsafe { std::slice::from_raw_parts(buffer, len) }.to_vec();
-            self.shared.borrow_mut().frame_buffer = Some((pixels, width as u32, height as u32));
+            let pixels = unsafe { std::slice::from_raw_parts(buffer, len) };
+
+            if type_ == PaintElementType::POPUP {
+                let mut shared = self.shared.borrow_mut();
+                if let Some(popup) = shared.popup.as_mut() {
+                    popup.pixels = Some((pixels.to_vec(), width as u32, height as u32));
+                    shared.popup_changed = true;
+                }
+                return;
+            }
+
+            let rects = dirty_rects.map(|rects| {
+                rects
+                    .iter()
+                    .map(|rect| Rectangle {
+                        x: rect.x.max(0) as u32,
+                        y: rect.y.max(0) as u32,
+                        width: rect.width.max(0) as u32,
+                        height: rect.height.max(0) as u32,
+                    })
+                    .collect::<Vec<_>>()
+            });
+
+            let mut shared = self.shared.borrow_mut();
+            shared
+                .canvas
+                .paint(pixels, width as u32, height as u32, rects.as_deref());
+            // A paint not drained yet is painted over; keep its damage too.
+            shared.damage = match (shared.painted, shared.damage.take(), rects) {
+                (false, _, rects) => rects,
+                (true, Some(mut pending), Some(rects)) => {
+                    pending.extend(rects);
+                    Some(pending)
+                }
+                _ => None,
+            };
+            shared.painted = true;
         }
     }
 }
@@ -146,6 +380,26 @@ wrap_display_handler! {
             self.shared.borrow_mut().cursor_type = type_;
             0
         }
+
+        fn on_favicon_urlchange(
+            &self,
+            _browser: Option<&mut Browser>,
+            icon_urls: Option<&mut CefStringList>,
+        ) {
+            let first = icon_urls.and_then(|urls| urls.clone().into_iter().next());
+            if let Some(url) = first {
+                self.shared
+                    .borrow_mut()
+                    .events
+                    .push(WebViewEvent::FaviconChanged(url));
+            }
+        }
+
+        fn on_loading_progress_change(&self, _browser: Option<&mut Browser>, progress: f64) {
+            let mut shared = self.shared.borrow_mut();
+            let SharedState { load, events, .. } = &mut *shared;
+            load.progress(progress as f32, events);
+        }
     }
 }
 
@@ -160,11 +414,119 @@ wrap_life_span_handler! {
     }
 }
 
+wrap_load_handler! {
+    struct OsrLoadHandler {
+        shared: Rc<RefCell<SharedState>>,
+    }
+
+    impl LoadHandler {
+        fn on_loading_state_change(
+            &self,
+            _browser: Option<&mut Browser>,
+            is_loading: c_int,
+            _can_go_back: c_int,
+            _can_go_forward: c_int,
+        ) {
+            let mut shared = self.shared.borrow_mut();
+            let SharedState { load, events, .. } = &mut *shared;
+            if is_loading != 0 {
+                load.start(events);
+            } else {
+                load.finish(events);
+            }
+        }
+
+        fn on_load_start(
+            &self,
+            _browser: Option<&mut Browser>,
+            frame: Option<&mut Frame>,
+            _transition_type: TransitionType,
+        ) {
+            if frame.is_some_and(|f| f.is_main() != 0) {
+                let mut shared = self.shared.borrow_mut();
+                let SharedState { load, events, .. } = &mut *shared;
+                load.commit(events);
+            }
+        }
+
+        // CEF has no DOMContentLoaded hook; the main frame's load end is the
+        // closest milestone.
+        fn on_load_end(
+            &self,
+            _browser: Option<&mut Browser>,
+            frame: Option<&mut Frame>,
+            _http_status_code: c_int,
+        ) {
+            if frame.is_some_and(|f| f.is_main() != 0) {
+                let mut shared = self.shared.borrow_mut();
+                let SharedState { load, events, .. } = &mut *shared;
+                load.dom_ready(events);
+            }
+        }
+
+        fn on_load_error(
+            &self,
+            _browser: Option<&mut Browser>,
+            frame: Option<&mut Frame>,
+            _error_code: Errorcode,
+            error_text: Option<&CefString>,
+            failed_url: Option<&CefString>,
+        ) {
+            // Sub-frame failures don't fail the page.
+            if frame.is_some_and(|f| f.is_main() == 0) {
+                return;
+            }
+            let text = error_text.map(|t| t.to_string()).unwrap_or_default();
+            // Aborted loads are navigations superseded by another one.
+            if text.contains("ERR_ABORTED") {
+                return;
+            }
+            let url = failed_url.map(|u| u.to_string()).unwrap_or_default();
+            let error = if text.contains("TIMED_OUT") {
+                WebViewError::Timeout
+            } else {
+                WebViewError::Network(format!("{url}: {text}"))
+            };
+            self.shared
+                .borrow_mut()
+                .events
+                .push(WebViewEvent::Error(error));
+        }
+    }
+}
+
+wrap_dev_tools_message_observer! {
+    struct OsrScriptObserver {
+        shared: Rc<RefCell<SharedState>>,
+    }
+
+    impl DevToolsMessageObserver {
+        fn on_dev_tools_method_result(
+            &self,
+            _browser: Option<&mut Browser>,
+            message_id: c_int,
+            success: c_int,
+            result: Option<&[u8]>,
+        ) {
+            let mut shared = self.shared.borrow_mut();
+            // Scripts that timed out are no longer waited for
+            let Some((script_id, _)) = shared.scripts.remove(&message_id) else {
+                return;
+            };
+            let result = parse_script_reply(success != 0, result.unwrap_or_default());
+            shared
+                .events
+                .push(WebViewEvent::ScriptResult(script_id, result));
+        }
+    }
+}
+
 wrap_client! {
     struct OsrClient {
         render_handler: RenderHandler,
         display_handler: DisplayHandler,
         life_span_handler: LifeSpanHandler,
+        load_handler: LoadHandler,
     }
 
     impl Client {
@@ -179,6 +541,10 @@ wrap_client! {
         fn life_span_handler(&self) -> Option<LifeSpanHandler> {
             Some(self.life_span_handler.clone())
         }
+
+        fn load_handler(&self) -> Option<LoadHandler> {
+            Some(self.load_handler.clone())
+        }
     }
 }
 
@@ -189,9 +555,22 @@ struct CefView {
     url: String,
     title: String,
     cursor: Interaction,
+    /// The last view paint, without the popup.
+    view_frame: ImageInfo,
+    /// The view frame shown before `view_frame`, with where it differs from
+    /// `view_frame` (`None` for everywhere). Its buffer becomes the next
+    /// canvas once nothing shows it anymore.
+    retired: Option<(ImageInfo, Option<Vec<Rectangle<u32>>>)>,
+    /// `view_frame` with the popup composited over it, if one is open.
     last_frame: ImageInfo,
+    /// Where the popup was composited into `last_frame`, in physical pixels.
+    popup_rect: Option<Rectangle<u32>>,
+    /// Last pointer position, for wheel events.
+    pointer: Point,
     needs_render: bool,
     size: Size<u32>,
+    /// Keeps the observer that receives script results registered.
+    _script_observer: Option<Registration>,
 }
 
 /// Full browser engine backed by [CEF/Chromium](https://github.com/tauri-apps/cef-rs)
@@ -225,6 +604,7 @@ pub struct Cef {
     scale_factor: f32,
     initialized: bool,
     mouse_modifiers: u32,
+    pump: Arc<MessagePump>,
 }
 
 impl Default for Cef {
@@ -261,7 +641,8 @@ impl Default for Cef {
             ..Default::default()
         };
 
-        let mut app = OsrApp::new();
+        let pump = Arc::new(MessagePump::default());
+        let mut app = OsrApp::new(Arc::clone(&pump));
 
         let result = initialize(
             Some(args.as_main_args()),
@@ -275,6 +656,7 @@ impl Default for Cef {
             scale_factor: 1.0,
             initialized: result == 1,
             mouse_modifiers: 0,
+            pump,
         }
     }
 }
@@ -315,7 +697,7 @@ pub fn cef_subprocess_check() -> bool {
         true
     };
 
-    let mut app = OsrApp::new();
+    let mut app = OsrApp::new(Arc::default());
     let ret = execute_process(
         Some(args.as_main_args()),
         Some(&mut app),
@@ -351,6 +733,111 @@ impl Cef {
     }
 }
 
+/// Draw the popup over `view_frame` at its place in physical pixels and
+/// return the result with that rectangle. Without a painted popup the view
+/// frame is shown as is.
+///
+/// `view_damage` is where `view_frame` differs from the view frame under
+/// `last_frame`, or `None` when unknown. The result is marked as differing
+/// from `last_frame` there and where the popup is and was.
+fn compose_frame(
+    view_frame: &ImageInfo,
+    last_frame: &ImageInfo,
+    last_rect: Option<Rectangle<u32>>,
+    popup: Option<&Popup>,
+    scale: f32,
+    view_damage: Option<Vec<Rectangle<u32>>>,
+) -> (ImageInfo, Option<Rectangle<u32>>) {
+    let Some((popup, (pixels, popup_w, popup_h))) =
+        popup.and_then(|popup| Some((popup, popup.pixels.as_ref()?)))
+    else {
+        let frame = match (last_rect, view_damage) {
+            // The popup closed: the view shows through where it was.
+            (Some(old), Some(mut rects)) => {
+                rects.push(old);
+                view_frame.clone().with_damage(last_frame, rects)
+            }
+            _ => view_frame.clone(),
+        };
+        return (frame, None);
+    };
+
+    let (width, height) = (view_frame.image_width(), view_frame.image_height());
+    let x = ((popup.rect.x as f32 * scale).round() as u32).min(width);
+    let y = ((popup.rect.y as f32 * scale).round() as u32).min(height);
+    let w = (*popup_w).min(width - x);
+    let h = (*popup_h).min(height - y);
+
+    let mut composite = view_frame.raw_pixels().to_vec();
+    let row = 4 * w as usize;
+    for i in 0..h as usize {
+        let src = 4 * i * *popup_w as usize;
+        let dst = 4 * ((y as usize + i) * width as usize + x as usize);
+        composite[dst..dst + row].copy_from_slice(&pixels[src..src + row]);
+    }
+
+    let rect = Rectangle {
+        x,
+        y,
+        width: w,
+        height: h,
+    };
+    let frame = ImageInfo::new(composite, view_frame.format(), width, height);
+    let frame = match view_damage {
+        Some(mut rects) => {
+            rects.push(rect);
+            rects.extend(last_rect.filter(|old| *old != rect));
+            frame.with_damage(last_frame, rects)
+        }
+        None => frame,
+    };
+    (frame, Some(rect))
+}
+
+/// Decode the reply to a DevTools `Runtime.evaluate` call: the protocol
+/// error when it failed, otherwise the thrown exception or the value.
+fn parse_script_reply(success: bool, reply: &[u8]) -> Result<ScriptValue, WebViewError> {
+    let reply: serde_json::Value = serde_json::from_slice(reply)
+        .map_err(|e| WebViewError::Script(format!("malformed script reply: {e}")))?;
+    if !success {
+        let message = reply["message"].as_str().unwrap_or("script failed");
+        return Err(WebViewError::Script(message.to_string()));
+    }
+    if let Some(details) = reply.get("exceptionDetails") {
+        let message = details["exception"]["description"]
+            .as_str()
+            .or_else(|| details["text"].as_str())
+            .unwrap_or("script threw");
+        return Err(WebViewError::Script(message.to_string()));
+    }
+    let result = &reply["result"];
+    if let Some(value) = result.get("value") {
+        return Ok(json_to_script_value(value.clone()));
+    }
+    // NaN, the infinities, -0 and BigInts have no JSON form
+    Ok(result["unserializableValue"]
+        .as_str()
+        .and_then(|v| v.trim_end_matches('n').parse().ok())
+        .map_or(ScriptValue::Undefined, ScriptValue::Number))
+}
+
+fn json_to_script_value(value: serde_json::Value) -> ScriptValue {
+    match value {
+        serde_json::Value::Null => ScriptValue::Null,
+        serde_json::Value::Bool(b) => ScriptValue::Bool(b),
+        serde_json::Value::Number(n) => ScriptValue::Number(n.as_f64().unwrap_or(f64::NAN)),
+        serde_json::Value::String(s) => ScriptValue::String(s),
+        serde_json::Value::Array(items) => {
+            ScriptValue::Array(items.into_iter().map(json_to_script_value).collect())
+        }
+        serde_json::Value::Object(map) => ScriptValue::Object(
+            map.into_iter()
+                .map(|(k, v)| (k, json_to_script_value(v)))
+                .collect(),
+        ),
+    }
+}
+
 fn cursor_type_to_interaction(cursor: CursorType) -> Interaction {
     match cursor {
         CursorType::POINTER => Interaction::Pointer,
@@ -379,28 +866,141 @@ impl Engine for Cef {
         true
     }
 
+    fn capabilities(&self) -> Capabilities {
+        // Selection is Chromium-managed and not queryable.
+        Capabilities {
+            javascript: true,
+            history: true,
+            keyboard_input: true,
+            text_selection: false,
+            native_urls: true,
+        }
+    }
+
     fn update(&mut self) {
         if !self.initialized {
             return;
         }
 
+        // Cleared first: the work below may schedule more.
+        self.pump.scheduled.store(false, Ordering::Release);
         do_message_loop_work();
 
         for view in &mut self.views {
             let mut shared = view.shared.borrow_mut();
 
-            if let Some((pixels, w, h)) = shared.frame_buffer.take() {
-                view.last_frame = ImageInfo::new(pixels, PixelFormat::Bgra, w, h);
+            let view_painted = std::mem::take(&mut shared.painted);
+            let popup_changed = std::mem::take(&mut shared.popup_changed);
+            let mut view_damage = Some(Vec::new());
+            if view_painted {
+                // The canvas moves into the frame without a copy.
+                let canvas = std::mem::take(&mut shared.canvas);
+                let frame = ImageInfo::new(
+                    canvas.pixels,
+                    PixelFormat::Bgra,
+                    canvas.width,
+                    canvas.height,
+                );
+                let frame = match shared.damage.take() {
+                    Some(rects) => frame.with_damage(&view.view_frame, rects),
+                    None => frame,
+                };
+                view_damage = frame
+                    .damage_since(view.view_frame.frame())
+                    .map(<[_]>::to_vec);
+                let shown = std::mem::replace(&mut view.view_frame, frame);
+                // Paint into the buffer of the frame before, which the
+                // widget has let go of by now, unless it still holds it.
+                if let Some((old, stale)) = view.retired.take() {
+                    let (width, height) = (old.image_width(), old.image_height());
+                    if let Ok(pixels) = old.into_pixels().try_into_mut() {
+                        shared.canvas = Canvas {
+                            pixels: pixels.into(),
+                            width,
+                            height,
+                            stale: stale.zip(view_damage.clone()).map(|(mut stale, damage)| {
+                                stale.extend(damage);
+                                stale
+                            }),
+                        };
+                    }
+                }
+                view.retired = Some((shown, view_damage.clone()));
                 view.needs_render = false;
             }
+            if view_painted || popup_changed {
+                let (frame, popup_rect) = compose_frame(
+                    &view.view_frame,
+                    &view.last_frame,
+                    view.popup_rect,
+                    shared.popup.as_ref(),
+                    shared.scale_factor,
+                    view_damage,
+                );
+                view.last_frame = frame;
+                view.popup_rect = popup_rect;
+            }
             if let Some(url) = shared.url.take() {
-                view.url = url;
+                if url != view.url {
+                    view.url = url.clone();
+                    shared.events.push(WebViewEvent::UrlChanged(url));
+                }
             }
             if let Some(title) = shared.title.take() {
-                view.title = title;
+                if title != view.title {
+                    view.title = title.clone();
+                    shared.events.push(WebViewEvent::TitleChanged(title));
+                }
+            }
+            let cursor = cursor_type_to_interaction(shared.cursor_type);
+            if cursor != view.cursor {
+                view.cursor = cursor;
+                shared.events.push(WebViewEvent::CursorChanged(cursor));
             }
-            view.cursor = cursor_type_to_interaction(shared.cursor_type);
+            let SharedState {
+                scripts, events, ..
+            } = &mut *shared;
+            scripts.retain(|_, (script_id, sent)| {
+                let waiting = sent.elapsed() < SCRIPT_TIMEOUT;
+                if !waiting {
+                    events.push(WebViewEvent::ScriptResult(
+                        *script_id,
+                        Err(WebViewError::Script(format!(
+                            "no result after {}s",
+                            SCRIPT_TIMEOUT.as_secs()
+                        ))),
+                    ));
+                }
+                waiting
+            });
+        }
+    }
+
+    fn set_waker(&mut self, waker: Waker) {
+        *self.pump.waker.lock().unwrap() = Some(waker);
+    }
+
+    /// Also holds while a script is unanswered, so its timeout is noticed.
+    fn needs_update(&self) -> bool {
+        self.pump.scheduled.load(Ordering::Acquire)
+            || self
+                .views
+                .iter()
+                .any(|v| v.needs_render || !v.shared.borrow().scripts.is_empty())
+    }
+
+    fn take_events(&mut self) -> Vec<EngineEvent> {
+        let mut result = Vec::new();
+        for view in &self.views {
+            let mut shared = view.shared.borrow_mut();
+            result.extend(
+                shared
+                    .events
+                    .drain(..)
+                    .map(|e| EngineEvent::new(view.id, e)),
+            );
         }
+        result
     }
 
     fn render(&mut self, _size: Size<u32>) {
@@ -418,18 +1018,32 @@ impl Engine for Cef {
         let size = Size::new(w, h);
 
         let shared = Rc::new(RefCell::new(SharedState {
-            frame_buffer: None,
+            canvas: Canvas::default(),
+            painted: false,
+            damage: None,
+            popup: None,
+            popup_changed: false,
             url: None,
             title: None,
             cursor_type: CursorType::POINTER,
             size,
             scale_factor: self.scale_factor,
+            load: LoadTracker::default(),
+            scripts: HashMap::new(),
+            next_script: 1,
+            events: Vec::new(),
         }));
 
         let render_handler = OsrRenderHandler::new(Rc::clone(&shared));
         let display_handler = OsrDisplayHandler::new(Rc::clone(&shared));
         let life_span_handler = OsrLifeSpanHandler::new(Rc::clone(&shared));
-        let mut client = OsrClient::new(render_handler, display_handler, life_span_handler);
+        let load_handler = OsrLoadHandler::new(Rc::clone(&shared));
+        let mut client = OsrClient::new(
+            render_handler,
+            display_handler,
+            life_span_handler,
+            load_handler,
+        );
 
         let window_info = WindowInfo::default().set_as_windowless(0);
 
@@ -463,6 +1077,19 @@ impl Engine for Cef {
         )
         .expect("CEF browser creation failed");
 
+        let script_observer = browser.host().and_then(|host| {
+            host.add_dev_tools_message_observer(Some(&mut OsrScriptObserver::new(Rc::clone(
+                &shared,
+            ))))
+        });
+
+        if !url_str.is_empty() {
+            shared
+                .borrow_mut()
+                .events
+                .push(WebViewEvent::UrlChanged(url_str.clone()));
+        }
+
         let view = CefView {
             id,
             browser,
@@ -470,9 +1097,14 @@ impl Engine for Cef {
             url: url_str,
             title: String::new(),
             cursor: Interaction::Idle,
+            view_frame: ImageInfo::blank(w, h),
+            retired: None,
             last_frame: ImageInfo::blank(w, h),
+            popup_rect: None,
+            pointer: Point::ORIGIN,
             needs_render: true,
             size,
+            _script_observer: script_observer,
         };
         self.views.push(view);
         id
@@ -518,7 +1150,14 @@ impl Engine for Cef {
         let new_size = Size::new(w, h);
         for view in &mut self.views {
             view.size = new_size;
-            view.shared.borrow_mut().size = new_size;
+            let mut shared = view.shared.borrow_mut();
+            shared.size = new_size;
+            // Keep an open popup inside the new bounds
+            if let Some(popup) = shared.popup.as_mut() {
+                popup.place(popup.requested(), new_size);
+                shared.popup_changed = true;
+            }
+            drop(shared);
             if let Some(host) = view.browser.host() {
                 host.was_resized();
             }
@@ -562,11 +1201,9 @@ impl Engine for Cef {
             return;
         };
 
-        let me = MouseEvent {
-            x: point.x as c_int,
-            y: point.y as c_int,
-            modifiers,
-        };
+        view.pointer = point;
+        let (x, y) = popup_point(&view.shared.borrow(), point);
+        let me = MouseEvent { x, y, modifiers };
 
         match event {
             mouse::Event::ButtonPressed(button) => {
@@ -602,11 +1239,9 @@ impl Engine for Cef {
             return;
         };
 
-        let me = MouseEvent {
-            x: 0,
-            y: 0,
-            modifiers,
-        };
+        // At the pointer, so an open popup scrolls rather than the page.
+        let (x, y) = popup_point(&view.shared.borrow(), view.pointer);
+        let me = MouseEvent { x, y, modifiers };
 
         let (dx, dy) = match delta {
             mouse::ScrollDelta::Lines { x, y } => ((x * 40.0) as c_int, (y * 40.0) as c_int),
@@ -626,7 +1261,13 @@ impl Engine for Cef {
 
         match page_type {
             PageType::Url(url) => {
-                view.url = url.clone();
+                if url != view.url {
+                    view.url = url.clone();
+                    view.shared
+                        .borrow_mut()
+                        .events
+                        .push(WebViewEvent::UrlChanged(url.clone()));
+                }
                 let cef_url = CefString::from(url.as_str());
                 frame.load_url(Some(&cef_url));
             }
@@ -659,6 +1300,64 @@ impl Engine for Cef {
         }
     }
 
+    fn evaluate_script(
+        &mut self,
+        id: ViewId,
+        script_id: ScriptId,
+        script: String,
+    ) -> Result<(), WebViewError> {
+        let view = self
+            .find_view(id)
+            .ok_or_else(|| WebViewError::Script(format!("no view with id {id}")))?;
+        let host = view
+            .browser
+            .host()
+            .ok_or_else(|| WebViewError::Script("the view has no browser host".into()))?;
+        // DevTools evaluates in the page's main world and answers outside of
+        // it, so the page can neither see the result nor forge one, and it
+        // waits for a promise to settle.
+        let message_id = {
+            let mut shared = view.shared.borrow_mut();
+            let message_id = shared.next_script;
+            shared.next_script = message_id.wrapping_add(1).max(1);
+            shared
+                .scripts
+                .insert(message_id, (script_id, Instant::now()));
+            message_id
+        };
+        let message = serde_json::json!({
+            "id": message_id,
+            "method": "Runtime.evaluate",
+            "params": {
+                "expression": script,
+                "awaitPromise": true,
+                "returnByValue": true,
+            },
+        });
+        if host.send_dev_tools_message(Some(message.to_string().as_bytes())) == 0 {
+            view.shared.borrow_mut().scripts.remove(&message_id);
+            return Err(WebViewError::Script(
+                "DevTools did not accept the script".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn can_go_back(&self, id: ViewId) -> bool {
+        self.find_view(id)
+            .is_some_and(|v| v.browser.can_go_back() != 0)
+    }
+
+    fn can_go_forward(&self, id: ViewId) -> bool {
+        self.find_view(id)
+            .is_some_and(|v| v.browser.can_go_forward() != 0)
+    }
+
+    fn is_loading(&self, id: ViewId) -> bool {
+        self.find_view(id)
+            .is_some_and(|v| v.browser.is_loading() != 0)
+    }
+
     fn get_url(&self, id: ViewId) -> String {
         let Some(view) = self.find_view(id) else {
             return "about:blank".to_string();
@@ -0,0 +1,772 @@
+use std::cell::Cell;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::marker::PhantomData;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use iced::keyboard;
+use iced::mouse::{self, Interaction};
+use iced::{Point, Size};
+use rand::Rng;
+
+use super::{Capabilities, Engine, PageType, ViewId};
+use crate::{EngineEvent, ImageInfo, ScriptId, Tile, Waker, WebViewError, WebViewEvent};
+
+/// The `&mut self` half of [`Engine`], sent to the worker thread.
+/// View ids are the host's; the worker maps them to the engine's.
+enum Command {
+    Update,
+    Render(Size<u32>),
+    RequestRender(ViewId, Size<u32>),
+    NewView(ViewId, Size<u32>, Option<PageType>),
+    RemoveView(ViewId),
+    Focus,
+    Unfocus,
+    Resize(Size<u32>),
+    SetScaleFactor(f32),
+    SetWaker(Waker),
+    #[cfg(any(feature = "litehtml", feature = "blitz"))]
+    SetFetchPolicy(crate::FetchMode, crate::RetryPolicy),
+    KeyboardEvent(ViewId, keyboard::Event),
+    MouseEvent(ViewId, Point, mouse::Event),
+    Scroll(ViewId, mouse::ScrollDelta),
+    Goto(ViewId, PageType),
+    Refresh(ViewId),
+    GoForward(ViewId),
+    GoBack(ViewId),
+    EvaluateScript(ViewId, ScriptId, String),
+    ScrollToFragment(ViewId, String),
+    SetCssCache(ViewId, HashMap<String, String>),
+    LoadImage(ViewId, String, Vec<u8>, bool),
+    ImageFetchFailed(ViewId, String),
+    FlushStagedImages(ViewId, Size<u32>),
+    Shutdown,
+}
+
+/// Sent once by the worker after constructing the engine.
+struct Info {
+    handles_urls: bool,
+    capabilities: Capabilities,
+}
+
+/// What a view looked like after the worker finished a batch.
+#[derive(Default)]
+struct Snapshot {
+    url: String,
+    title: String,
+    cursor: Interaction,
+    frame: ImageInfo,
+    /// Commands processed when `frame` was drawn.
+    rendered: u64,
+    tiles: Vec<Tile>,
+    scroll_y: f32,
+    content_height: f32,
+    selection_rects: Vec<[f32; 4]>,
+    selected_text: Option<String>,
+    can_go_back: bool,
+    can_go_forward: bool,
+    is_loading: bool,
+    handles_urls: bool,
+    capabilities: Capabilities,
+}
+
+/// Sent by the worker after every batch of commands.
+struct Report {
+    views: Vec<(ViewId, Snapshot)>,
+    events: Vec<EngineEvent>,
+    pending_images: Vec<(ViewId, String, String, bool)>,
+    anchor_clicks: Vec<(ViewId, String)>,
+    capabilities: Capabilities,
+    needs_update: bool,
+}
+
+/// The host's copy of a view, refreshed from reports.
+struct HostView {
+    state: Snapshot,
+    /// Frames drawn before this command were rendered for a size or page
+    /// the view no longer has.
+    frame_seq: u64,
+}
+
+/// Runs a CPU engine on a worker thread so layout and painting never block
+/// the iced update loop.
+///
+/// `EngineHost<Blitz>` or `EngineHost<Litehtml>` drop in wherever the engine
+/// itself would go. Calls are forwarded over a channel and answered from the
+/// state the worker reported last, so frames, titles and events arrive a tick
+/// later than they would inline. Frames rendered before a resize, scale change
+/// or navigation are dropped rather than shown.
+///
+/// The engine is built on the worker thread and never leaves it, so it does
+/// not need to be `Send`. Dropping the host does not wait for it: the worker
+/// finishes the batch it is on, then drops the engine and exits.
+pub struct EngineHost<E: Engine> {
+    commands: Sender<Command>,
+    reports: Receiver<Report>,
+    /// Commands sent so far.
+    sent: Cell<u64>,
+    views: HashMap<ViewId, HostView>,
+    /// View ids in creation order.
+    order: Vec<ViewId>,
+    handles_urls: bool,
+    capabilities: Capabilities,
+    needs_update: bool,
+    events: Vec<EngineEvent>,
+    pending_images: Vec<(ViewId, String, String, bool)>,
+    anchor_clicks: HashMap<ViewId, String>,
+    _engine: PhantomData<fn() -> E>,
+}
+
+impl<E: Engine + Default + 'static> Default for EngineHost<E> {
+    fn default() -> Self {
+        Self::new(E::default)
+    }
+}
+
+impl<E: Engine + 'static> EngineHost<E> {
+    /// Start a worker thread running the engine `make` builds, e.g. an
+    /// [`AnyEngine`](crate::AnyEngine) with a custom router.
+    ///
+    /// Blocks until the engine has been constructed.
+    pub fn new(make: impl FnOnce() -> E + Send + 'static) -> Self {
+        let (commands, command_rx) = mpsc::channel();
+        let (report_tx, reports) = mpsc::channel();
+        let (info_tx, info_rx) = mpsc::channel();
+        thread::Builder::new()
+            .name("iced_webview-engine".into())
+            .spawn(move || {
+                let engine = make();
+                let _ = info_tx.send(Info {
+                    handles_urls: engine.handles_urls(),
+                    capabilities: engine.capabilities(),
+                });
+                Worker::new(engine).run(command_rx, report_tx);
+            })
+            .expect("failed to spawn the engine worker thread");
+        let info = info_rx
+            .recv()
+            .expect("the engine worker exited during startup");
+
+        Self {
+            commands,
+            reports,
+            sent: Cell::new(0),
+            views: HashMap::new(),
+            order: Vec::new(),
+            handles_urls: info.handles_urls,
+            capabilities: info.capabilities,
+            needs_update: false,
+            events: Vec::new(),
+            pending_images: Vec::new(),
+            anchor_clicks: HashMap::new(),
+            _engine: PhantomData,
+        }
+    }
+
+    /// Queue a command, returning its sequence number. A dead worker
+    /// (the engine panicked) leaves the views frozen.
+    fn send(&self, command: Command) -> u64 {
+        let seq = self.sent.get() + 1;
+        self.sent.set(seq);
+        let _ = self.commands.send(command);
+        seq
+    }
+
+    /// Queue a command after which the view's current frame is stale.
+    fn send_invalidating(&mut self, id: ViewId, command: Command) {
+        let seq = self.send(command);
+        if let Some(view) = self.views.get_mut(&id) {
+            view.frame_seq = seq;
+        }
+    }
+
+    /// Queue a command after which every view's frame is stale.
+    fn send_invalidating_all(&mut self, command: Command) {
+        let seq = self.send(command);
+        for view in self.views.values_mut() {
+            view.frame_seq = seq;
+        }
+    }
+
+    /// Apply every report the worker has sent since the last call.
+    fn drain(&mut self) {
+        while let Ok(report) = self.reports.try_recv() {
+            for (id, mut snapshot) in report.views {
+                let Some(view) = self.views.get_mut(&id) else {
+                    continue;
+                };
+                if snapshot.rendered < view.frame_seq {
+                    // Stale frame: keep the one on screen and its geometry
+                    let state = &mut view.state;
+                    std::mem::swap(&mut snapshot.frame, &mut state.frame);
+                    std::mem::swap(&mut snapshot.tiles, &mut state.tiles);
+                    std::mem::swap(&mut snapshot.selection_rects, &mut state.selection_rects);
+                    snapshot.scroll_y = state.scroll_y;
+                    snapshot.content_height = state.content_height;
+                }
+                view.state = snapshot;
+            }
+            self.events.extend(report.events);
+            self.pending_images.extend(report.pending_images);
+            self.anchor_clicks.extend(report.anchor_clicks);
+            self.capabilities = report.capabilities;
+            self.needs_update = report.needs_update;
+        }
+    }
+
+    fn view(&self, id: ViewId) -> Option<&Snapshot> {
+        self.views.get(&id).map(|v| &v.state)
+    }
+}
+
+impl<E: Engine> Drop for EngineHost<E> {
+    fn drop(&mut self) {
+        // Detached: joining would block the UI until the current render ends
+        let _ = self.commands.send(Command::Shutdown);
+    }
+}
+
+impl<E: Engine + 'static> Engine for EngineHost<E> {
+    fn update(&mut self) {
+        self.send(Command::Update);
+        self.drain();
+    }
+
+    fn render(&mut self, size: Size<u32>) {
+        self.send(Command::Render(size));
+    }
+
+    fn request_render(&mut self, id: ViewId, size: Size<u32>) {
+        self.send(Command::RequestRender(id, size));
+    }
+
+    fn new_view(&mut self, size: Size<u32>, content: Option<PageType>) -> ViewId {
+        let id = rand::thread_rng().gen();
+        let url = match &content {
+            Some(PageType::Url(url)) => url.clone(),
+            _ => String::new(),
+        };
+        let seq = self.send(Command::NewView(id, size, content));
+        self.views.insert(
+            id,
+            HostView {
+                state: Snapshot {
+                    url,
+                    is_loading: true,
+                    // Until the first report, assume the engine-wide answer
+                    handles_urls: self.handles_urls,
+                    capabilities: self.capabilities,
+                    ..Snapshot::default()
+                },
+                frame_seq: seq,
+            },
+        );
+        self.order.push(id);
+        id
+    }
+
+    fn remove_view(&mut self, id: ViewId) {
+        self.send(Command::RemoveView(id));
+        self.views.remove(&id);
+        self.order.retain(|v| *v != id);
+        self.anchor_clicks.remove(&id);
+    }
+
+    fn has_view(&self, id: ViewId) -> bool {
+        self.views.contains_key(&id)
+    }
+
+    fn focus(&mut self) {
+        self.send(Command::Focus);
+    }
+
+    fn unfocus(&self) {
+        self.send(Command::Unfocus);
+    }
+
+    fn resize(&mut self, size: Size<u32>) {
+        self.send_invalidating_all(Command::Resize(size));
+    }
+
+    fn set_scale_factor(&mut self, scale: f32) {
+        self.send_invalidating_all(Command::SetScaleFactor(scale));
+    }
+
+    fn set_waker(&mut self, waker: Waker) {
+        self.send(Command::SetWaker(waker));
+    }
+
+    #[cfg(any(feature = "litehtml", feature = "blitz"))]
+    fn set_fetch_policy(&mut self, mode: crate::FetchMode, retry: crate::RetryPolicy) {
+        self.send(Command::SetFetchPolicy(mode, retry));
+    }
+
+    fn needs_update(&self) -> bool {
+        self.needs_update
+    }
+
+    fn handles_urls(&self) -> bool {
+        self.handles_urls
+    }
+
+    fn view_handles_urls(&self, id: ViewId) -> bool {
+        self.view(id).map_or(self.handles_urls, |v| v.handles_urls)
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    fn view_capabilities(&self, id: ViewId) -> Capabilities {
+        self.view(id).map_or(self.capabilities, |v| v.capabilities)
+    }
+
+    fn handle_keyboard_event(&mut self, id: ViewId, event: keyboard::Event) {
+        self.send(Command::KeyboardEvent(id, event));
+    }
+
+    fn handle_mouse_event(&mut self, id: ViewId, point: Point, event: mouse::Event) {
+        self.send(Command::MouseEvent(id, point, event));
+    }
+
+    fn scroll(&mut self, id: ViewId, delta: mouse::ScrollDelta) {
+        self.send(Command::Scroll(id, delta));
+    }
+
+    fn goto(&mut self, id: ViewId, page_type: PageType) {
+        self.send_invalidating(id, Command::Goto(id, page_type));
+    }
+
+    fn refresh(&mut self, id: ViewId) {
+        self.send_invalidating(id, Command::Refresh(id));
+    }
+
+    fn go_forward(&mut self, id: ViewId) {
+        self.send_invalidating(id, Command::GoForward(id));
+    }
+
+    fn go_back(&mut self, id: ViewId) {
+        self.send_invalidating(id, Command::GoBack(id));
+    }
+
+    fn evaluate_script(
+        &mut self,
+        id: ViewId,
+        script_id: ScriptId,
+        script: String,
+    ) -> Result<(), WebViewError> {
+        if !self.view_capabilities(id).javascript {
+            return Err(WebViewError::Unsupported("JavaScript evaluation".into()));
+        }
+        self.send(Command::EvaluateScript(id, script_id, script));
+        Ok(())
+    }
+
+    fn can_go_back(&self, id: ViewId) -> bool {
+        self.view(id).is_some_and(|v| v.can_go_back)
+    }
+
+    fn can_go_forward(&self, id: ViewId) -> bool {
+        self.view(id).is_some_and(|v| v.can_go_forward)
+    }
+
+    fn is_loading(&self, id: ViewId) -> bool {
+        self.view(id).is_some_and(|v| v.is_loading)
+    }
+
+    fn get_url(&self, id: ViewId) -> String {
+        self.view(id).map(|v| v.url.clone()).unwrap_or_default()
+    }
+
+    fn get_title(&self, id: ViewId) -> String {
+        self.view(id).map(|v| v.title.clone()).unwrap_or_default()
+    }
+
+    fn get_cursor(&self, id: ViewId) -> Interaction {
+        self.view(id).map(|v| v.cursor).unwrap_or_default()
+    }
+
+    fn get_view(&self, id: ViewId) -> &ImageInfo {
+        static BLANK: std::sync::LazyLock<ImageInfo> = std::sync::LazyLock::new(ImageInfo::default);
+        self.view(id).map(|v| &v.frame).unwrap_or(&BLANK)
+    }
+
+    fn get_tiles(&self, id: ViewId) -> &[Tile] {
+        self.view(id).map(|v| v.tiles.as_slice()).unwrap_or(&[])
+    }
+
+    fn get_scroll_y(&self, id: ViewId) -> f32 {
+        self.view(id).map(|v| v.scroll_y).unwrap_or(0.0)
+    }
+
+    fn get_content_height(&self, id: ViewId) -> f32 {
+        self.view(id).map(|v| v.content_height).unwrap_or(0.0)
+    }
+
+    fn get_selected_text(&self, id: ViewId) -> Option<String> {
+        self.view(id).and_then(|v| v.selected_text.clone())
+    }
+
+    fn get_selection_rects(&self, id: ViewId) -> &[[f32; 4]] {
+        self.view(id)
+            .map(|v| v.selection_rects.as_slice())
+            .unwrap_or(&[])
+    }
+
+    fn take_anchor_click(&mut self, id: ViewId) -> Option<String> {
+        self.drain();
+        self.anchor_clicks.remove(&id)
+    }
+
+    /// Queues the scroll and returns `true` for a live view; the worker finds
+    /// out later whether the fragment exists.
+    fn scroll_to_fragment(&mut self, id: ViewId, fragment: &str) -> bool {
+        self.send(Command::ScrollToFragment(id, fragment.to_string()));
+        self.has_view(id)
+    }
+
+    fn take_pending_images(&mut self) -> Vec<(ViewId, String, String, bool)> {
+        self.drain();
+        std::mem::take(&mut self.pending_images)
+    }
+
+    fn take_events(&mut self) -> Vec<EngineEvent> {
+        self.drain();
+        std::mem::take(&mut self.events)
+    }
+
+    fn set_css_cache(&mut self, id: ViewId, cache: HashMap<String, String>) {
+        self.send(Command::SetCssCache(id, cache));
+    }
+
+    fn load_image_from_bytes(
+        &mut self,
+        id: ViewId,
+        url: &str,
+        bytes: &[u8],
+        redraw_on_ready: bool,
+    ) {
+        self.send(Command::LoadImage(
+            id,
+            url.to_string(),
+            bytes.to_vec(),
+            redraw_on_ready,
+        ));
+    }
+
+    fn image_fetch_failed(&mut self, id: ViewId, url: &str) {
+        self.send(Command::ImageFetchFailed(id, url.to_string()));
+    }
+
+    fn flush_staged_images(&mut self, id: ViewId, size: Size<u32>) {
+        self.send(Command::FlushStagedImages(id, size));
+    }
+
+    fn view_ids(&self) -> Vec<ViewId> {
+        self.order.clone()
+    }
+}
+
+/// Rendering work deferred to the end of a batch, so a burst of commands
+/// costs one render per view.
+enum Deferred {
+    Render(Size<u32>),
+    RequestRender(ViewId, Size<u32>),
+    FlushStagedImages(ViewId, Size<u32>),
+}
+
+struct Worker<E> {
+    engine: E,
+    /// Host id to engine id.
+    views: HashMap<ViewId, ViewId>,
+    waker: Option<Waker>,
+    seq: u64,
+    /// Host id to the last reported frame id and the command count it was
+    /// drawn at.
+    frames: HashMap<ViewId, (u64, u64)>,
+    /// `needs_update` as last reported.
+    needs_update: bool,
+    /// Scripts the engine refused, reported with the next batch.
+    script_errors: Vec<EngineEvent>,
+}
+
+impl<E: Engine> Worker<E> {
+    fn new(engine: E) -> Self {
+        Self {
+            engine,
+            views: HashMap::new(),
+            waker: None,
+            seq: 0,
+            frames: HashMap::new(),
+            needs_update: false,
+            script_errors: Vec::new(),
+        }
+    }
+
+    fn run(mut self, commands: Receiver<Command>, reports: Sender<Report>) {
+        while let Ok(first) = commands.recv() {
+            let mut update = false;
+            let mut deferred = Vec::new();
+            for command in std::iter::once(first).chain(commands.try_iter()) {
+                self.seq += 1;
+                match command {
+                    Command::Shutdown => return,
+                    Command::Update => update = true,
+                    Command::Render(size) => defer(&mut deferred, Deferred::Render(size)),
+                    Command::RequestRender(id, size) => {
+                        defer(&mut deferred, Deferred::RequestRender(id, size))
+                    }
+                    Command::FlushStagedImages(id, size) => {
+                        defer(&mut deferred, Deferred::FlushStagedImages(id, size))
+                    }
+                    command => self.apply(command),
+                }
+            }
+
+            if update {
+                self.engine.update();
+            }
+            let mut redrawn = HashSet::new();
+            for work in deferred {
+                match work {
+                    Deferred::Render(size) => {
+                        self.engine.render(size);
+                        redrawn.extend(self.views.keys().copied());
+                    }
+                    Deferred::RequestRender(id, size) => {
+                        if let Some(&inner) = self.views.get(&id) {
+                            self.engine.request_render(inner, size);
+                            redrawn.insert(id);
+                        }
+                    }
+                    Deferred::FlushStagedImages(id, size) => {
+                        if let Some(&inner) = self.views.get(&id) {
+                            self.engine.flush_staged_images(inner, size);
+                            redrawn.insert(id);
+                        }
+                    }
+                }
+            }
+
+            let (report, news) = self.report(&redrawn);
+            if reports.send(report).is_err() {
+                return;
+            }
+            // Waking makes the widget update, which sends another batch, so
+            // an idle engine has to stay quiet or the two never rest.
+            if news {
+                if let Some(waker) = &self.waker {
+                    waker.wake();
+                }
+            }
+        }
+    }
+
+    fn apply(&mut self, command: Command) {
+        let engine = &mut self.engine;
+        let inner = |id: &ViewId| self.views.get(id).copied();
+        match command {
+            Command::NewView(id, size, content) => {
+                let inner = engine.new_view(size, content);
+                self.views.insert(id, inner);
+            }
+            Command::RemoveView(id) => {
+                if let Some(inner) = self.views.remove(&id) {
+                    engine.remove_view(inner);
+                }
+                self.frames.remove(&id);
+            }
+            Command::Focus => engine.focus(),
+            Command::Unfocus => engine.unfocus(),
+            Command::Resize(size) => engine.resize(size),
+            Command::SetScaleFactor(scale) => engine.set_scale_factor(scale),
+            Command::SetWaker(waker) => {
+                engine.set_waker(waker.clone());
+                self.waker = Some(waker);
+            }
+            #[cfg(any(feature = "litehtml", feature = "blitz"))]
+            Command::SetFetchPolicy(mode, retry) => engine.set_fetch_policy(mode, retry),
+            Command::KeyboardEvent(id, event) => {
+                if let Some(inner) = inner(&id) {
+                    engine.handle_keyboard_event(inner, event);
+                }
+            }
+            Command::MouseEvent(id, point, event) => {
+                if let Some(inner) = inner(&id) {
+                    engine.handle_mouse_event(inner, point, event);
+                }
+            }
+            Command::Scroll(id, delta) => {
+                if let Some(inner) = inner(&id) {
+                    engine.scroll(inner, delta);
+                }
+            }
+            Command::Goto(id, page) => {
+                if let Some(inner) = inner(&id) {
+                    engine.goto(inner, page);
+                }
+            }
+            Command::Refresh(id) => {
+                if let Some(inner) = inner(&id) {
+                    engine.refresh(inner);
+                }
+            }
+            Command::GoForward(id) => {
+                if let Some(inner) = inner(&id) {
+                    engine.go_forward(inner);
+                }
+            }
+            Command::GoBack(id) => {
+                if let Some(inner) = inner(&id) {
+                    engine.go_back(inner);
+                }
+            }
+            Command::EvaluateScript(id, script_id, script) => {
+                let result = match inner(&id) {
+                    Some(inner) => engine.evaluate_script(inner, script_id, script),
+                    None => Err(WebViewError::Script(format!("no view with id {id}"))),
+                };
+                if let Err(error) = result {
+                    self.script_errors.push(EngineEvent::new(
+                        id,
+                        WebViewEvent::ScriptResult(script_id, Err(error)),
+                    ));
+                }
+            }
+            Command::ScrollToFragment(id, fragment) => {
+                if let Some(inner) = inner(&id) {
+                    engine.scroll_to_fragment(inner, &fragment);
+                }
+            }
+            Command::SetCssCache(id, cache) => {
+                if let Some(inner) = inner(&id) {
+                    engine.set_css_cache(inner, cache);
+                }
+            }
+            Command::LoadImage(id, url, bytes, redraw_on_ready) => {
+                if let Some(inner) = inner(&id) {
+                    engine.load_image_from_bytes(inner, &url, &bytes, redraw_on_ready);
+                }
+            }
+            Command::ImageFetchFailed(id, url) => {
+                if let Some(inner) = inner(&id) {
+                    engine.image_fetch_failed(inner, &url);
+                }
+            }
+            Command::Update
+            | Command::Render(_)
+            | Command::RequestRender(..)
+            | Command::FlushStagedImages(..)
+            | Command::Shutdown => unreachable!("handled by run"),
+        }
+    }
+
+    fn outer_id(&self, inner: ViewId) -> Option<ViewId> {
+        self.views
+            .iter()
+            .find(|(_, v)| **v == inner)
+            .map(|(id, _)| *id)
+    }
+
+    /// Snapshot every view, and tell whether the report carries anything
+    /// the host has not seen: events, a new frame, images to fetch, an
+    /// anchor click or a change in `needs_update`.
+    ///
+    /// A frame counts as drawn in this batch when the view was rendered or
+    /// the engine handed over a new frame, so a frame left over from before
+    /// a resize or navigation keeps its older count.
+    fn report(&mut self, redrawn: &HashSet<ViewId>) -> (Report, bool) {
+        let engine = &mut self.engine;
+        let mut views = Vec::with_capacity(self.views.len());
+        let mut anchor_clicks = Vec::new();
+        let mut new_frame = false;
+        for (&id, &inner) in &self.views {
+            let frame = engine.get_view(inner).clone();
+            let drawn = match self.frames.entry(id) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    new_frame = true;
+                    entry.insert((frame.frame(), self.seq))
+                }
+            };
+            if drawn.0 != frame.frame() {
+                new_frame = true;
+            }
+            if drawn.0 != frame.frame() || redrawn.contains(&id) {
+                *drawn = (frame.frame(), self.seq);
+            }
+            views.push((
+                id,
+                Snapshot {
+                    url: engine.get_url(inner),
+                    title: engine.get_title(inner),
+                    cursor: engine.get_cursor(inner),
+                    frame,
+                    rendered: drawn.1,
+                    tiles: engine.get_tiles(inner).to_vec(),
+                    scroll_y: engine.get_scroll_y(inner),
+                    content_height: engine.get_content_height(inner),
+                    selection_rects: engine.get_selection_rects(inner).to_vec(),
+                    selected_text: engine.get_selected_text(inner),
+                    can_go_back: engine.can_go_back(inner),
+                    can_go_forward: engine.can_go_forward(inner),
+                    is_loading: engine.is_loading(inner),
+                    handles_urls: engine.view_handles_urls(inner),
+                    capabilities: engine.view_capabilities(inner),
+                },
+            ));
+            if let Some(href) = engine.take_anchor_click(inner) {
+                anchor_clicks.push((id, href));
+            }
+        }
+
+        let mut events = std::mem::take(&mut self.script_errors);
+        events.extend(
+            self.engine
+                .take_events()
+                .into_iter()
+                .filter_map(|e| Some(EngineEvent::new(self.outer_id(e.view)?, e.event))),
+        );
+        let pending_images: Vec<_> = self
+            .engine
+            .take_pending_images()
+            .into_iter()
+            .filter_map(|(view, src, baseurl, redraw_on_ready)| {
+                Some((self.outer_id(view)?, src, baseurl, redraw_on_ready))
+            })
+            .collect();
+
+        let needs_update = self.engine.needs_update();
+        let news = new_frame
+            || !events.is_empty()
+            || !pending_images.is_empty()
+            || !anchor_clicks.is_empty()
+            || needs_update != self.needs_update;
+        self.needs_update = needs_update;
+        let report = Report {
+            views,
+            events,
+            pending_images,
+            anchor_clicks,
+            capabilities: self.engine.capabilities(),
+            needs_update,
+        };
+        (report, news)
+    }
+}
+
+/// Queue `work` unless the same work is already queued; a newer size for
+/// the same view replaces the older one.
+fn defer(deferred: &mut Vec<Deferred>, work: Deferred) {
+    let same_target = |queued: &Deferred| match (queued, &work) {
+        (Deferred::Render(_), Deferred::Render(_)) => true,
+        (Deferred::RequestRender(a, _), Deferred::RequestRender(b, _))
+        | (Deferred::FlushStagedImages(a, _), Deferred::FlushStagedImages(b, _)) => a == b,
+        _ => false,
+    };
+    match deferred.iter_mut().find(|queued| same_target(queued)) {
+        Some(queued) => *queued = work,
+        None => deferred.push(work),
+    }
+}
@@ -1,5 +1,8 @@
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+use std::time::Duration;
 
 use iced::keyboard;
 use iced::mouse::{self, Interaction};
@@ -7,15 +10,17 @@ use iced::{Point, Size};
 use rand::Rng;
 use url::Url;
 
-use super::{Engine, PageType, PixelFormat, ViewId};
-use crate::ImageInfo;
+use super::load::LoadTracker;
+use super::tiles::{HoverDamage, TileCache, HOVER_THROTTLE};
+use super::{Capabilities, Engine, PageType, ViewId};
+use crate::{EngineEvent, ImageInfo, PixelFormat, Tile, WebViewError, WebViewEvent};
 
 use litehtml::pixbuf::PixbufContainer;
 use litehtml::selection::Selection;
 use litehtml::{
     css_escape_ident, BackgroundLayer, BorderRadiuses, Borders, Color, ConicGradient, Document,
-    DocumentContainer, DrawContext, FontDescription, FontHandle, FontMetrics, LinearGradient,
-    ListMarker, MediaFeatures, Position, RadialGradient, TextTransform,
+    DocumentContainer, DrawContext, Element, FontDescription, FontHandle, FontMetrics,
+    LinearGradient, ListMarker, MediaFeatures, Position, RadialGradient, TextTransform,
 };
 
 /// Wrapper around `PixbufContainer` that handles CSS import resolution
@@ -28,6 +33,13 @@ struct WebviewContainer {
     /// can resolve relative URLs against the correct context (stylesheet
     /// URL, not the page URL).
     image_baseurls: RefCell<HashMap<String, String>>,
+    /// Document title reported by litehtml during parsing.
+    caption: Option<String>,
+    /// Viewport size reported to layout, in logical pixels. The pixel
+    /// buffer only ever holds one tile or patch, so layout can't use its
+    /// size.
+    layout_width: Option<u32>,
+    layout_height: Option<u32>,
 }
 
 impl WebviewContainer {
@@ -37,6 +49,9 @@ impl WebviewContainer {
             base_url: String::new(),
             css_cache: RefCell::new(HashMap::new()),
             image_baseurls: RefCell::new(HashMap::new()),
+            caption: None,
+            layout_width: None,
+            layout_height: None,
         }
     }
 
@@ -76,8 +91,80 @@ impl WebviewContainer {
     }
 }
 
+/// A heap allocation reached only through a raw pointer.
+///
+/// The references `Deref` hands out are derived from that pointer and live
+/// no longer than the statement using them, so a [`ContainerRef`] to the
+/// same allocation stays valid in between. They must not be held across a
+/// call into the `Document`, whose callbacks use the `ContainerRef`.
+struct RawBox<T>(NonNull<T>);
+
+impl<T> RawBox<T> {
+    fn new(value: T) -> Self {
+        Self(NonNull::from(Box::leak(Box::new(value))))
+    }
+
+    /// A `'static` reference for a `Document` to keep.
+    ///
+    /// # Safety
+    ///
+    /// The reference must be gone before the box is dropped, and no other
+    /// reference into the box may be used while it is.
+    unsafe fn leak_mut(&self) -> &'static mut T {
+        unsafe { &mut *self.0.as_ptr() }
+    }
+}
+
+impl<T> Deref for RawBox<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: see the type docs.
+        unsafe { self.0.as_ref() }
+    }
+}
+
+impl<T> DerefMut for RawBox<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: see the type docs.
+        unsafe { self.0.as_mut() }
+    }
+}
+
+impl<T> Drop for RawBox<T> {
+    fn drop(&mut self) {
+        // SAFETY: allocated by `Box` in `new` and not freed since.
+        drop(unsafe { Box::from_raw(self.0.as_ptr()) });
+    }
+}
+
+/// What a `Document` borrows instead of the container itself: a pointer to
+/// the view's container, dereferenced only while a callback runs.
+///
+/// The `Document` keeps its `&mut` to this pointer, never to the container,
+/// so the view may use the container between document calls, e.g. to load
+/// image data into a live document.
+struct ContainerRef(NonNull<WebviewContainer>);
+
+impl Deref for ContainerRef {
+    type Target = WebviewContainer;
+
+    fn deref(&self) -> &WebviewContainer {
+        // SAFETY: the container outlives every document of its view, and
+        // the view holds no reference into it while a callback runs.
+        unsafe { self.0.as_ref() }
+    }
+}
+
+impl DerefMut for ContainerRef {
+    fn deref_mut(&mut self) -> &mut WebviewContainer {
+        // SAFETY: as for `deref`.
+        unsafe { self.0.as_mut() }
+    }
+}
+
 // Delegate everything to inner, override import_css, set_base_url, load_image
-impl DocumentContainer for WebviewContainer {
+impl DocumentContainer for ContainerRef {
     fn create_font(&mut self, descr: &FontDescription) -> (FontHandle, FontMetrics) {
         self.inner.create_font(descr)
     }
@@ -152,6 +239,7 @@ impl DocumentContainer for WebviewContainer {
         self.inner.draw_borders(hdc, borders, draw_pos, root);
     }
     fn set_caption(&mut self, caption: &str) {
+        self.caption = Some(caption.to_string());
         self.inner.set_caption(caption);
     }
     fn set_base_url(&mut self, base_url: &str) {
@@ -188,43 +276,81 @@ impl DocumentContainer for WebviewContainer {
         self.inner.del_clip();
     }
     fn get_viewport(&self) -> Position {
-        self.inner.get_viewport()
+        let mut viewport = self.inner.get_viewport();
+        if let Some(width) = self.layout_width {
+            viewport.width = width as _;
+        }
+        if let Some(height) = self.layout_height {
+            viewport.height = height as _;
+        }
+        viewport
     }
     fn get_media_features(&self) -> MediaFeatures {
-        self.inner.get_media_features()
+        let mut features = self.inner.get_media_features();
+        if let Some(width) = self.layout_width {
+            features.width = width as _;
+            features.device_width = width as f32 * self.inner.scale_factor();
+        }
+        if let Some(height) = self.layout_height {
+            features.height = height as _;
+        }
+        features
     }
 }
 
+type MeasureFn = Box<dyn Fn(&str, FontHandle) -> f32>;
+
 /// Persistent document and selection state for a view.
 ///
 /// # Safety
 ///
-/// The `doc` field borrows from the `Box<WebviewContainer>` in the parent
-/// `LitehtmlView`. The container is heap-allocated for address stability.
-/// `doc_state` is declared before `container` in `LitehtmlView` for correct
-/// drop order — it must be dropped first.
-///
-/// To avoid aliasing UB, `doc_state` is temporarily taken out (`Option::take`)
-/// whenever the container is accessed directly. This ensures only one `&mut`
-/// to the container data exists at a time.
-type MeasureFn = Box<dyn Fn(&str, FontHandle) -> f32>;
-
+/// `doc` holds a `&'static mut` to `_callbacks`, which is only a pointer to
+/// the container in the parent `LitehtmlView`. Both allocations are reached
+/// through raw pointers ([`RawBox`]), so the view's own short-lived
+/// references to the container never invalidate the document's.
+/// `_callbacks` is declared after `doc` so it drops after it, and
+/// `doc_state` is declared before `container` in `LitehtmlView` so the
+/// document is gone before the container it points to.
 struct DocumentState {
     doc: Document<'static>,
     measure: MeasureFn,
     selection: Selection<'static>,
+    /// What `doc` points to; dropped after it.
+    _callbacks: RawBox<ContainerRef>,
+}
+
+/// Parse `html` into a document whose callbacks reach `container`.
+fn parse_document(
+    html: &str,
+    container: &RawBox<WebviewContainer>,
+) -> Result<(Document<'static>, RawBox<ContainerRef>), litehtml::CreateError> {
+    let callbacks = RawBox::new(ContainerRef(container.0));
+    // SAFETY: the document is kept next to `callbacks` and dropped first
+    // (see `DocumentState`), and nothing else dereferences `callbacks`.
+    let doc = Document::from_html(html, unsafe { callbacks.leak_mut() }, None, None)?;
+    Ok((doc, callbacks))
 }
 
 struct LitehtmlView {
     id: ViewId,
     // IMPORTANT: doc_state must be declared before container so it drops first.
     doc_state: Option<DocumentState>,
-    container: Box<WebviewContainer>,
+    container: RawBox<WebviewContainer>,
     html: String,
     url: String,
     title: String,
     cursor: Interaction,
+    /// Topmost visible tile, for `get_view`.
     last_frame: ImageInfo,
+    tiles: TileCache,
+    /// `:hover` changes not yet repainted.
+    hover: HoverDamage,
+    /// Last pointer position handed to litehtml, as document `(x, y)` and
+    /// client `(x, y)`.
+    hover_point: Option<[f32; 4]>,
+    /// Shallowest depth in the hovered element chain where the hover target
+    /// changed since the last repaint.
+    hover_depth: Option<usize>,
     needs_render: bool,
     /// Fetched image bytes waiting to be flushed into the container.
     /// Accumulated between render cycles so multiple images cause only
@@ -238,6 +364,11 @@ struct LitehtmlView {
     size: Size<u32>,
     drag_origin: Option<(f32, f32)>,
     drag_active: bool,
+    /// Load lifecycle of the current page. Images handed out by
+    /// `take_pending_images` count as its subresources.
+    load: LoadTracker,
+    /// Events not yet drained by `take_events`.
+    events: Vec<WebViewEvent>,
 }
 
 /// CPU-based HTML rendering engine backed by litehtml.
@@ -247,6 +378,7 @@ struct LitehtmlView {
 pub struct Litehtml {
     views: Vec<LitehtmlView>,
     scale_factor: f32,
+    hover_throttle: Option<Duration>,
 }
 
 impl Default for Litehtml {
@@ -254,11 +386,21 @@ impl Default for Litehtml {
         Self {
             views: Vec::new(),
             scale_factor: 1.0,
+            hover_throttle: Some(HOVER_THROTTLE),
         }
     }
 }
 
 impl Litehtml {
+    /// Repaint `:hover` styles by redrawing the elements that entered or
+    /// left the hover state into the cached tiles when litehtml reports a
+    /// change, at most once per `throttle`. On by default with a 50 ms
+    /// throttle; `None` leaves hover styles unrendered.
+    pub fn hover_repaint(mut self, throttle: Option<Duration>) -> Self {
+        self.hover_throttle = throttle;
+        self
+    }
+
     fn find_view(&self, id: ViewId) -> Option<&LitehtmlView> {
         self.views.iter().find(|v| v.id == id)
     }
@@ -271,13 +413,15 @@ impl Litehtml {
 /// Build a persistent Document for the view, storing it alongside its
 /// text-measurement closure and a fresh Selection.
 ///
-/// Drops any existing document state first (releasing the container borrow),
-/// then resizes the container, creates a new Document, and renders the layout.
+/// Drops any existing document state first, then resizes the container,
+/// creates a new Document, and renders the layout.
 fn rebuild_document(view: &mut LitehtmlView) {
     view.doc_state = None;
+    // A new document has nothing hovered
+    view.hover_point = None;
+    view.hover_depth = None;
 
-    // Flush any staged images while doc_state is None (safe — no Document
-    // holds a borrow of the container).
+    // Flush any staged images before parsing so the layout sees them.
     if !view.staged_images.is_empty() {
         for (url, bytes, _) in view.staged_images.drain(..) {
             view.container.inner_mut().load_image_data(&url, &bytes);
@@ -291,61 +435,46 @@ fn rebuild_document(view: &mut LitehtmlView) {
         return;
     }
 
+    let previous_height = view.content_height;
+    view.container.caption = None;
+
     // Pass 1: use a tall viewport so CSS `100vh` doesn't cap content height.
     let layout_h = h.max(10_000);
-    view.container.inner_mut().resize(w, layout_h);
+    view.container.inner_mut().resize(w, h);
+    view.container.layout_width = Some(w);
+    view.container.layout_height = Some(layout_h);
 
     // Capture the text measurement closure before borrowing the container
     let measure = view.container.inner().text_measure_fn();
 
-    // SAFETY: Manual lifetime extension is required here due to litehtml API constraints.
-    //
-    // The litehtml Document<'a> type is invariant over its lifetime parameter and
-    // requires a mutable borrow of the container. This makes it incompatible with
-    // self-referential struct crates like ouroboros or self_cell, which cannot handle:
-    //   1. Lifetime invariance (they require covariance)
-    //   2. Multiple mutable borrows from the same field (Document and Selection)
-    //
-    // The unsafe lifetime extension to 'static is safe because:
-    //   1. container is Box<WebviewContainer> — heap-allocated with a stable address
-    //   2. doc_state is declared before container in LitehtmlView → drops first
-    //   3. doc_state is set to None before any container modification or drop
-    //   4. The Document never outlives the container it borrows from
-    //
-    // This pattern has been carefully reviewed and is the standard approach for
-    // self-referential structures when safe abstractions are incompatible.
-    let container_ptr = &mut *view.container as *mut WebviewContainer;
-    let container_ref: &'static mut WebviewContainer = unsafe { &mut *container_ptr };
-
-    match Document::from_html(&view.html, container_ref, None, None) {
+    match parse_document(&view.html, &view.container) {
         Err(e) => {
-            eprintln!("litehtml: from_html failed: {e:?}");
+            view.events
+                .push(WebViewEvent::Error(WebViewError::Parse(format!("{e:?}"))));
         }
-        Ok(mut doc) => {
+        Ok((mut doc, callbacks)) => {
             let _ = doc.render(w as f32);
             let measured = doc.height();
 
-            // Pass 2: if content overflows the layout viewport, re-layout so
-            // `100vh` covers the full content and overflow clips don't cut it off.
+            // Pass 2: if content overflows the layout viewport, re-parse with
+            // a viewport that tall so `100vh` covers the full content and
+            // overflow clips don't cut it off.
             if measured > layout_h as f32 {
                 let final_h = measured.ceil() as u32;
-
-                // Drop the document BEFORE resizing. Calling resize while doc
-                // holds a &mut borrow of the container would create two live
-                // &mut references — undefined behavior.
                 drop(doc);
+                drop(callbacks);
 
-                view.container.inner_mut().resize(w, final_h);
+                view.container.layout_height = Some(final_h);
                 let measure2 = view.container.inner().text_measure_fn();
 
-                let container_ptr2 = &mut *view.container as *mut WebviewContainer;
-                let container_ref2: &'static mut WebviewContainer = unsafe { &mut *container_ptr2 };
-
-                match Document::from_html(&view.html, container_ref2, None, None) {
+                match parse_document(&view.html, &view.container) {
                     Err(e) => {
-                        eprintln!("litehtml: from_html pass 2 failed: {e:?}");
+                        view.events
+                            .push(WebViewEvent::Error(WebViewError::Parse(format!(
+                                "pass 2: {e:?}"
+                            ))));
                     }
-                    Ok(mut doc2) => {
+                    Ok((mut doc2, callbacks2)) => {
                         let _ = doc2.render(w as f32);
                         view.content_height = doc2.height();
 
@@ -354,6 +483,7 @@ fn rebuild_document(view: &mut LitehtmlView) {
                             doc: doc2,
                             measure: Box::new(measure2),
                             selection,
+                            _callbacks: callbacks2,
                         });
                     }
                 }
@@ -365,73 +495,242 @@ fn rebuild_document(view: &mut LitehtmlView) {
                     doc,
                     measure: Box::new(measure),
                     selection,
+                    _callbacks: callbacks,
                 });
             }
         }
     }
+
+    let title = view.container.caption.take().unwrap_or_default();
+    if title != view.title {
+        view.title = title.clone();
+        view.events.push(WebViewEvent::TitleChanged(title));
+    }
+    if view.content_height != previous_height {
+        view.events.push(WebViewEvent::ContentSizeChanged(Size::new(
+            w as f32,
+            view.content_height,
+        )));
+    }
+    if view.doc_state.is_some() {
+        view.load.dom_ready(&mut view.events);
+    } else {
+        // Parse failed; nothing more will arrive for this document.
+        view.load.finish(&mut view.events);
+    }
 }
 
-/// Draw the document into the pixel buffer and capture `last_frame`.
-///
-/// Resizes the container to fit the full content height, disables CSS
-/// overflow clips, draws, then captures the pixels.
-///
-/// To avoid aliasing UB, the document is temporarily taken out of
-/// `doc_state` while the container is mutated (resize, clip flags),
-/// then restored for the `draw` call, and taken out again for cleanup.
+/// Redraw the tiles around the scroll position after the document changed.
 fn capture_frame(view: &mut LitehtmlView) {
-    let w = view.size.width;
-    let full_h = (view.content_height.ceil() as u32).max(1);
-
-    // Take doc_state out so we can safely mutate the container.
-    let doc_state = view.doc_state.take();
+    view.tiles.invalidate();
+    draw_tiles(view);
+}
 
-    view.container.inner_mut().resize(w, full_h);
-    view.container.inner_mut().set_ignore_overflow_clips(true);
+/// Draw the tiles around the scroll position that aren't cached yet and
+/// capture the topmost one as `last_frame`.
+fn draw_tiles(view: &mut LitehtmlView) {
+    if view.doc_state.is_none() {
+        return;
+    }
+    let LitehtmlView {
+        doc_state,
+        container,
+        tiles,
+        scroll_y,
+        size,
+        content_height,
+        ..
+    } = &mut *view;
+    let w = size.width as f32;
+    tiles.update(
+        *scroll_y,
+        size.height as f32,
+        *content_height,
+        |top, height| draw_region(doc_state, container, 0.0, top, w, height),
+    );
+
+    if let Some(tile) = view.tiles.visible().first() {
+        view.last_frame = tile.image().clone();
+    }
+    view.needs_render = false;
+}
 
-    // Restore doc_state and draw.
-    view.doc_state = doc_state;
-    if let Some(ref mut ds) = view.doc_state {
+/// Rasterize `[x, y, width, height]` of the document (logical coordinates)
+/// and return its premultiplied RGBA pixels with their physical size.
+///
+/// The container's pixel buffer is resized to the region and the document is
+/// drawn shifted by its offset with CSS overflow clips disabled. Layout keeps
+/// seeing the view's size through `layout_width` and `layout_height`.
+fn draw_region(
+    doc_state: &mut Option<DocumentState>,
+    container: &mut RawBox<WebviewContainer>,
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+) -> (Vec<u8>, u32, u32) {
+    let region_w = (width.ceil() as u32).max(1);
+    let region_h = (height.ceil() as u32).max(1);
+
+    container.inner_mut().resize(region_w, region_h);
+    container.inner_mut().set_ignore_overflow_clips(true);
+
+    if let Some(ds) = doc_state {
         let clip = Position {
             x: 0.0,
             y: 0.0,
-            width: w as f32,
-            height: full_h as f32,
+            width: region_w as f32,
+            height: region_h as f32,
         };
-        ds.doc.draw(DrawContext(0), 0.0, 0.0, Some(clip));
+        ds.doc.draw(DrawContext(0), -x, -y, Some(clip));
     }
 
-    // Take doc_state out again to safely access the container.
-    let doc_state = view.doc_state.take();
-    view.container.inner_mut().set_ignore_overflow_clips(false);
+    container.inner_mut().set_ignore_overflow_clips(false);
+    let phys_w = container.inner().width();
+    let phys_h = container.inner().height();
+    let pixels = container.inner().pixels().to_vec();
 
-    let phys_w = view.container.inner().width();
-    let phys_h = view.container.inner().height();
-    let pixels = unpremultiply_rgba(view.container.inner().pixels());
-    view.last_frame = ImageInfo::new(pixels, PixelFormat::Rgba, phys_w, phys_h);
-    view.needs_render = false;
+    (pixels, phys_w, phys_h)
+}
 
-    view.doc_state = doc_state;
+/// The element at a pointer position `[x, y, client_x, client_y]` and its
+/// ancestors, root first.
+fn hover_chain<'a>(doc: &'a Document, point: [f32; 4]) -> Vec<Element<'a>> {
+    let [x, y, client_x, client_y] = point;
+    let mut chain: Vec<_> = std::iter::successors(
+        doc.get_element_by_point(x, y, client_x, client_y),
+        Element::parent,
+    )
+    .collect();
+    chain.reverse();
+    chain
+}
+
+/// How deep the chains under two pointer positions agree, i.e. the depth
+/// of the first element that entered or left `:hover`. litehtml elements
+/// have no identity, so elements are told apart by their placement.
+fn hover_divergence(before: &[Element], after: &[Element]) -> usize {
+    before
+        .iter()
+        .zip(after)
+        .take_while(|(a, b)| a.placement() == b.placement())
+        .count()
+}
+
+/// Bounds of an element and everything below it, as `[x, y, width, height]`
+/// in logical document coordinates. Covers descendants that overflow the
+/// element, such as a dropdown opened by `:hover`.
+fn subtree_bounds(element: Element) -> Option<[f32; 4]> {
+    let mut bounds: Option<[f32; 4]> = None;
+    let mut stack = vec![element];
+    while let Some(element) = stack.pop() {
+        let pos = element.placement();
+        let [x0, y0, x1, y1] = bounds.get_or_insert([pos.x, pos.y, pos.x, pos.y]);
+        *x0 = x0.min(pos.x);
+        *y0 = y0.min(pos.y);
+        *x1 = x1.max(pos.x + pos.width);
+        *y1 = y1.max(pos.y + pos.height);
+        stack.extend((0..element.children_count()).filter_map(|i| element.child_at(i)));
+    }
+    bounds.map(|[x0, y0, x1, y1]| [x0, y0, x1 - x0, y1 - y0])
+}
+
+/// Hand a pointer move to litehtml and record where its `:hover` change
+/// repaints: the elements that left the hover state, as laid out now, and
+/// the ones that entered it, again after the restyle in `repaint_hover`.
+fn hover_move(view: &mut LitehtmlView, point: [f32; 4], record: bool) {
+    let Some(ref mut state) = view.doc_state else {
+        return;
+    };
+    let before = view
+        .hover_point
+        .map(|old| hover_chain(&state.doc, old))
+        .unwrap_or_default();
+    let after = hover_chain(&state.doc, point);
+    let depth = hover_divergence(&before, &after);
+    let left = before.into_iter().nth(depth).and_then(subtree_bounds);
+    let entered = after.into_iter().nth(depth).and_then(subtree_bounds);
+
+    let [x, y, client_x, client_y] = point;
+    let changed = state.doc.on_mouse_over(x, y, client_x, client_y);
+    view.hover_point = Some(point);
+    if changed && record {
+        view.hover.add(left);
+        view.hover.add(entered);
+        view.hover_depth = Some(view.hover_depth.map_or(depth, |d| d.min(depth)));
+    }
 }
 
-/// Render the full document into the pixel buffer and update `last_frame`.
+/// Re-layout after `:hover` changes and patch the damaged regions into the
+/// cached tiles, at most once per `throttle`.
 ///
-/// The buffer covers the entire content height so the widget can scroll
-/// by offsetting the draw position — no re-render needed per scroll.
+/// Falls back to a full redraw when the new styles changed the document
+/// height.
+fn repaint_hover(view: &mut LitehtmlView, throttle: Duration) {
+    if view.needs_render {
+        // A full redraw is coming anyway
+        view.hover.clear();
+        view.hover_depth = None;
+        return;
+    }
+    let Some(mut rects) = view.hover.take_due(throttle) else {
+        return;
+    };
+    let Some(ref mut state) = view.doc_state else {
+        return;
+    };
+
+    let _ = state.doc.render(view.size.width as f32);
+    if state.doc.height() != view.content_height {
+        view.needs_render = true;
+        return;
+    }
+    // The hover targets as restyled, which may have grown
+    if let (Some(depth), Some(point)) = (view.hover_depth.take(), view.hover_point) {
+        rects.extend(
+            hover_chain(&state.doc, point)
+                .into_iter()
+                .nth(depth)
+                .and_then(subtree_bounds),
+        );
+    }
+
+    let LitehtmlView {
+        doc_state,
+        container,
+        tiles,
+        ..
+    } = &mut *view;
+    for rect in rects {
+        tiles.patch(rect, |x, y, width, height| {
+            draw_region(doc_state, container, x, y, width, height)
+        });
+    }
+
+    if let Some(tile) = view.tiles.visible().first() {
+        view.last_frame = tile.image().clone();
+    }
+}
+
+/// Redraw the document into tiles and update `last_frame`.
+///
+/// Only the tiles near the viewport are rasterized; the widget composites
+/// them at their document offsets, and scrolling draws the rest on demand.
 fn draw_view(view: &mut LitehtmlView) {
     capture_frame(view);
 }
 
 /// Flush staged image bytes into the container and redraw.
 ///
-/// Drops the document first to release its borrow on the container, loads
-/// the image data safely, then rebuilds the document from the stored HTML.
-/// This avoids all mutable aliasing — the previous approach used raw
-/// pointers to mutate the container while the Document held a `&mut` borrow,
-/// which was undefined behavior under Rust's aliasing rules.
+/// litehtml marks images whose size is already known from the layout
+/// (explicit dimensions, backgrounds) with `redraw_on_ready`; those only
+/// need a redraw. Any other image re-runs layout on the existing document.
+/// Only when the content outgrows the layout viewport is the document
+/// rebuilt from the stored HTML, like the second pass of `rebuild_document`.
 ///
-/// The rebuild cost is acceptable: `Document::from_html` + `render` is fast
-/// for typical HTML, and this path only runs when async image fetches complete.
+/// Loading image data into the container under a live document is sound
+/// because the document only holds a [`ContainerRef`], not a borrow of the
+/// container.
 fn flush_images_and_redraw(view: &mut LitehtmlView) {
     if view.staged_images.is_empty() {
         return;
@@ -442,19 +741,53 @@ fn flush_images_and_redraw(view: &mut LitehtmlView) {
         return;
     }
 
-    // Drop the document to release its borrow on the container.
-    view.doc_state = None;
+    let relayout = view
+        .staged_images
+        .iter()
+        .any(|(_, _, redraw_on_ready)| !redraw_on_ready);
 
-    // Now safe to mutate the container — no Document holds a borrow.
     for (url, bytes, _) in view.staged_images.drain(..) {
         view.container.inner_mut().load_image_data(&url, &bytes);
     }
 
-    // Rebuild the document from stored HTML (includes render + draw).
-    rebuild_document(view);
+    if relayout && !relayout_document(view) {
+        rebuild_document(view);
+    }
     capture_frame(view);
 }
 
+/// Re-run layout on the current document after images changed element
+/// sizes. Returns `false` when the content no longer fits the layout
+/// viewport, so `100vh` needs a rebuild to cover it.
+fn relayout_document(view: &mut LitehtmlView) -> bool {
+    let layout_h = view.container.layout_height.unwrap_or(view.size.height);
+    let Some(ref mut state) = view.doc_state else {
+        return true;
+    };
+
+    let _ = state.doc.render(view.size.width as f32);
+    let height = state.doc.height();
+    if height > layout_h as f32 {
+        return false;
+    }
+
+    // Selection rectangles were measured against the old layout
+    state.selection.clear();
+    if !view.selection_rects.is_empty() {
+        view.selection_rects.clear();
+        view.events
+            .push(WebViewEvent::SelectionChanged(String::new()));
+    }
+    if height != view.content_height {
+        view.content_height = height;
+        view.events.push(WebViewEvent::ContentSizeChanged(Size::new(
+            view.size.width as f32,
+            height,
+        )));
+    }
+    true
+}
+
 /// Main render entry point: rebuilds the document if needed, then draws.
 fn render_view(view: &mut LitehtmlView) {
     let w = view.size.width;
@@ -468,6 +801,7 @@ fn render_view(view: &mut LitehtmlView) {
         let phys_w = view.container.inner().width();
         let phys_h = view.container.inner().height();
         view.last_frame = ImageInfo::blank(phys_w, phys_h);
+        view.tiles.clear();
         view.needs_render = false;
         return;
     }
@@ -484,26 +818,6 @@ fn render_view(view: &mut LitehtmlView) {
     }
 }
 
-/// Convert premultiplied-alpha RGBA pixels to straight alpha.
-///
-/// litehtml's pixbuf backend (tiny-skia) stores premultiplied RGBA, but
-/// iced's `image::Handle::from_rgba` expects straight (unpremultiplied) alpha.
-fn unpremultiply_rgba(pixels: &[u8]) -> Vec<u8> {
-    let mut result = Vec::with_capacity(pixels.len());
-    for chunk in pixels.chunks_exact(4) {
-        let a = chunk[3] as u32;
-        if a == 0 {
-            result.extend_from_slice(&[0, 0, 0, 0]);
-        } else {
-            let r = ((chunk[0] as u32 * 255 + a / 2) / a).min(255) as u8;
-            let g = ((chunk[1] as u32 * 255 + a / 2) / a).min(255) as u8;
-            let b = ((chunk[2] as u32 * 255 + a / 2) / a).min(255) as u8;
-            result.extend_from_slice(&[r, g, b, chunk[3]]);
-        }
-    }
-    result
-}
-
 /// Map a CSS cursor value from litehtml to an iced mouse interaction.
 fn css_cursor_to_interaction(cursor: &str) -> Interaction {
     match cursor {
@@ -535,24 +849,64 @@ impl Engine for Litehtml {
         false
     }
 
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            text_selection: true,
+            ..Capabilities::default()
+        }
+    }
+
     fn update(&mut self) {
-        // No-op: litehtml has no async work or background tasks.
+        // litehtml has no async work of its own. A load is finished once
+        // every image handed out has arrived and been drawn.
+        for view in &mut self.views {
+            if view.load.is_dom_ready()
+                && view.load.pending() == 0
+                && view.staged_images.is_empty()
+                && !view.needs_render
+            {
+                view.load.finish(&mut view.events);
+            }
+        }
+    }
+
+    fn needs_update(&self) -> bool {
+        // Images in flight arrive as widget messages, so only a pending
+        // draw, staged images or a load that is ready to finish need ticks.
+        self.views.iter().any(|view| {
+            view.needs_render
+                || view.hover.is_pending()
+                || !view.staged_images.is_empty()
+                || (view.load.is_dom_ready() && view.load.pending() == 0)
+        })
     }
 
     fn render(&mut self, _size: Size<u32>) {
         for view in &mut self.views {
+            if let Some(throttle) = self.hover_throttle {
+                repaint_hover(view, throttle);
+            }
             if view.needs_render {
                 render_view(view);
+            } else {
+                draw_tiles(view);
             }
         }
     }
 
     fn request_render(&mut self, id: ViewId, _size: Size<u32>) {
+        let hover_throttle = self.hover_throttle;
         let Some(view) = self.find_view_mut(id) else {
             return;
         };
+        if let Some(throttle) = hover_throttle {
+            repaint_hover(view, throttle);
+        }
         if view.needs_render {
             render_view(view);
+        } else {
+            // Tiles scrolled into view since the last call
+            draw_tiles(view);
         }
     }
 
@@ -579,15 +933,32 @@ impl Engine for Litehtml {
             _ => String::new(),
         };
 
+        let mut load = LoadTracker::default();
+        let mut events = Vec::new();
+        if !url.is_empty() {
+            events.push(WebViewEvent::UrlChanged(url.clone()));
+            load.start(&mut events);
+        } else if !html.is_empty() {
+            load.commit(&mut events);
+        }
+
+        let mut container = RawBox::new(WebviewContainer::new(w, h, self.scale_factor));
+        container.base_url = url.clone();
+
         let mut view = LitehtmlView {
             id,
             doc_state: None,
-            container: Box::new(WebviewContainer::new(w, h, self.scale_factor)),
+            container,
             html,
             url,
             title: String::new(),
             cursor: Interaction::Idle,
             last_frame: ImageInfo::blank(w, h),
+            // tiny-skia draws premultiplied RGBA
+            tiles: TileCache::new(PixelFormat::RgbaPremultiplied),
+            hover: HoverDamage::default(),
+            hover_point: None,
+            hover_depth: None,
             needs_render: true,
             staged_images: Vec::new(),
             selection_rects: Vec::new(),
@@ -596,6 +967,8 @@ impl Engine for Litehtml {
             size,
             drag_origin: None,
             drag_active: false,
+            load,
+            events,
         };
 
         render_view(&mut view);
@@ -667,22 +1040,27 @@ impl Engine for Litehtml {
                     state.doc.on_lbutton_down(point.x, doc_y, point.x, point.y);
                     state.selection.clear();
                 }
-                view.selection_rects.clear();
+                if !view.selection_rects.is_empty() {
+                    view.selection_rects.clear();
+                    view.events
+                        .push(WebViewEvent::SelectionChanged(String::new()));
+                }
             }
             mouse::Event::CursorMoved { .. } => {
+                let hover_repaint = self.hover_throttle.is_some();
                 let Some(view) = self.find_view_mut(id) else {
                     return;
                 };
 
-                // Notify litehtml of mouse movement for :hover and cursor updates
-                if let Some(ref mut state) = view.doc_state {
-                    let doc_y = point.y + view.scroll_y;
-                    state.doc.on_mouse_over(point.x, doc_y, point.x, point.y);
+                // Notify litehtml of mouse movement for :hover and cursor
+                // updates.
+                let doc_y = point.y + view.scroll_y;
+                hover_move(view, [point.x, doc_y, point.x, point.y], hover_repaint);
+                let cursor = css_cursor_to_interaction(view.container.inner().cursor());
+                if cursor != view.cursor {
+                    view.cursor = cursor;
+                    view.events.push(WebViewEvent::CursorChanged(cursor));
                 }
-                // Take doc_state out to avoid aliasing while reading cursor.
-                let doc_state = view.doc_state.take();
-                view.cursor = css_cursor_to_interaction(view.container.inner().cursor());
-                view.doc_state = doc_state;
 
                 if let Some((ox, oy)) = view.drag_origin {
                     let dx = point.x - ox;
@@ -733,19 +1111,36 @@ impl Engine for Litehtml {
                     state.doc.on_lbutton_up(point.x, doc_y, point.x, point.y);
                 }
                 // Discard anchor clicks produced during text selection.
-                // Take doc_state out to avoid aliasing with container.
                 if was_dragging {
-                    let doc_state = view.doc_state.take();
                     view.container.inner_mut().take_anchor_click();
-                    view.doc_state = doc_state;
+
+                    let text = view
+                        .doc_state
+                        .as_ref()
+                        .and_then(|s| s.selection.selected_text())
+                        .unwrap_or_default();
+                    view.events.push(WebViewEvent::SelectionChanged(text));
                 }
             }
             mouse::Event::CursorLeft => {
+                let hover_repaint = self.hover_throttle.is_some();
                 if let Some(view) = self.find_view_mut(id) {
                     if let Some(ref mut state) = view.doc_state {
-                        state.doc.on_mouse_leave();
+                        // The whole hovered chain leaves the hover state
+                        let left = view
+                            .hover_point
+                            .take()
+                            .and_then(|old| hover_chain(&state.doc, old).into_iter().next())
+                            .and_then(subtree_bounds);
+                        if state.doc.on_mouse_leave() && hover_repaint {
+                            view.hover.add(left);
+                        }
+                    }
+                    if view.cursor != Interaction::Idle {
+                        view.cursor = Interaction::Idle;
+                        view.events
+                            .push(WebViewEvent::CursorChanged(Interaction::Idle));
                     }
-                    view.cursor = Interaction::Idle;
                 }
             }
             _ => {}
@@ -786,13 +1181,18 @@ impl Engine for Litehtml {
                 view.html = html;
                 view.scroll_y = 0.0;
                 view.needs_render = true;
+                view.load.commit(&mut view.events);
+                if view.html.is_empty() {
+                    view.load.finish(&mut view.events);
+                }
             }
             PageType::Url(url) => {
-                // Take doc_state out to avoid aliasing with container.
-                let doc_state = view.doc_state.take();
                 view.container.base_url = url.clone();
-                view.doc_state = doc_state;
-                view.url = url;
+                if url != view.url {
+                    view.url = url.clone();
+                    view.events.push(WebViewEvent::UrlChanged(url));
+                }
+                view.load.start(&mut view.events);
             }
         }
     }
@@ -814,6 +1214,10 @@ impl Engine for Litehtml {
         // No-op: no navigation history.
     }
 
+    fn is_loading(&self, id: ViewId) -> bool {
+        self.find_view(id).is_some_and(|v| v.load.is_loading())
+    }
+
     fn get_url(&self, id: ViewId) -> String {
         let Some(view) = self.find_view(id) else {
             return "about:blank".to_string();
@@ -842,6 +1246,10 @@ impl Engine for Litehtml {
         self.find_view(id).map(|v| &v.last_frame).unwrap_or(&BLANK)
     }
 
+    fn get_tiles(&self, id: ViewId) -> &[Tile] {
+        self.find_view(id).map(|v| v.tiles.visible()).unwrap_or(&[])
+    }
+
     fn get_scroll_y(&self, id: ViewId) -> f32 {
         self.find_view(id).map(|v| v.scroll_y).unwrap_or(0.0)
     }
@@ -867,19 +1275,16 @@ impl Engine for Litehtml {
 
     fn take_anchor_click(&mut self, id: ViewId) -> Option<String> {
         let view = self.find_view_mut(id)?;
-        // Take doc_state out to avoid aliasing with container.
-        let doc_state = view.doc_state.take();
         let result = view.container.inner_mut().take_anchor_click();
-        view.doc_state = doc_state;
         result
     }
 
     fn take_pending_images(&mut self) -> Vec<(ViewId, String, String, bool)> {
         let mut result = Vec::new();
         for view in &mut self.views {
-            // Take doc_state out to avoid aliasing with container.
-            let doc_state = view.doc_state.take();
-            for (src, redraw_on_ready) in view.container.inner_mut().take_pending_images() {
+            let pending = view.container.inner_mut().take_pending_images();
+            view.load.requested(pending.len(), &mut view.events);
+            for (src, redraw_on_ready) in pending {
                 let baseurl = view
                     .container
                     .image_baseurls
@@ -889,7 +1294,14 @@ impl Engine for Litehtml {
                     .unwrap_or_default();
                 result.push((view.id, src, baseurl, redraw_on_ready));
             }
-            view.doc_state = doc_state;
+        }
+        result
+    }
+
+    fn take_events(&mut self) -> Vec<EngineEvent> {
+        let mut result = Vec::new();
+        for view in &mut self.views {
+            result.extend(view.events.drain(..).map(|e| EngineEvent::new(view.id, e)));
         }
         result
     }
@@ -904,6 +1316,7 @@ impl Engine for Litehtml {
         let Some(view) = self.find_view_mut(id) else {
             return;
         };
+        view.load.completed(&mut view.events);
         if let Some(existing) = view.staged_images.iter_mut().find(|(u, _, _)| u == url) {
             existing.1 = bytes.to_vec();
             existing.2 = redraw_on_ready;
@@ -913,14 +1326,18 @@ impl Engine for Litehtml {
         }
     }
 
+    fn image_fetch_failed(&mut self, id: ViewId, _url: &str) {
+        let Some(view) = self.find_view_mut(id) else {
+            return;
+        };
+        view.load.completed(&mut view.events);
+    }
+
     fn set_css_cache(&mut self, id: ViewId, cache: HashMap<String, String>) {
         let Some(view) = self.find_view_mut(id) else {
             return;
         };
-        // Take doc_state out to avoid aliasing with container.
-        let doc_state = view.doc_state.take();
         view.container.set_css_cache(cache);
-        view.doc_state = doc_state;
     }
 
     fn scroll_to_fragment(&mut self, id: ViewId, fragment: &str) -> bool {
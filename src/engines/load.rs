@@ -0,0 +1,136 @@
+use crate::WebViewEvent;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Phase {
+    #[default]
+    Idle,
+    Started,
+    Committed,
+    DomReady,
+}
+
+/// Turns an engine's loading milestones into the `Load*` events, in order
+/// and without duplicates.
+///
+/// Progress reaches one half when the DOM is ready; the rest follows the
+/// subresources. Engines that fetch them through the widget count them with
+/// `requested` / `completed`, others report a fraction through `subresources`.
+/// Engines that know the overall fraction report it through `progress`.
+#[derive(Debug, Default)]
+pub(crate) struct LoadTracker {
+    phase: Phase,
+    requested: usize,
+    completed: usize,
+    progress: f32,
+}
+
+impl LoadTracker {
+    /// Whether a navigation is in flight.
+    pub(crate) fn is_loading(&self) -> bool {
+        self.phase != Phase::Idle
+    }
+
+    /// Whether the document is ready and only subresources are outstanding.
+    #[cfg_attr(not(any(feature = "litehtml", feature = "blitz")), allow(dead_code))]
+    pub(crate) fn is_dom_ready(&self) -> bool {
+        self.phase == Phase::DomReady
+    }
+
+    /// Subresources requested but not yet completed.
+    #[cfg_attr(not(feature = "litehtml"), allow(dead_code))]
+    pub(crate) fn pending(&self) -> usize {
+        self.requested - self.completed
+    }
+
+    /// A navigation began. Restarts the lifecycle if one was in flight.
+    pub(crate) fn start(&mut self, events: &mut Vec<WebViewEvent>) {
+        *self = Self {
+            phase: Phase::Started,
+            ..Self::default()
+        };
+        events.push(WebViewEvent::LoadStarted);
+        events.push(WebViewEvent::LoadProgress(0.0));
+    }
+
+    /// A new document replaced the previous one. Implies `start` when the
+    /// document arrived without a navigation, e.g. raw HTML.
+    pub(crate) fn commit(&mut self, events: &mut Vec<WebViewEvent>) {
+        if self.phase != Phase::Started {
+            self.start(events);
+        }
+        self.phase = Phase::Committed;
+        events.push(WebViewEvent::LoadCommitted);
+    }
+
+    /// The committed document is parsed and laid out.
+    pub(crate) fn dom_ready(&mut self, events: &mut Vec<WebViewEvent>) {
+        if self.phase != Phase::Committed {
+            return;
+        }
+        self.phase = Phase::DomReady;
+        events.push(WebViewEvent::DomReady);
+        self.report(events);
+    }
+
+    /// `count` more subresources are being fetched.
+    #[cfg_attr(not(feature = "litehtml"), allow(dead_code))]
+    pub(crate) fn requested(&mut self, count: usize, events: &mut Vec<WebViewEvent>) {
+        if count == 0 || !self.is_loading() {
+            return;
+        }
+        self.requested += count;
+        self.report(events);
+    }
+
+    /// One subresource arrived or failed.
+    #[cfg_attr(not(feature = "litehtml"), allow(dead_code))]
+    pub(crate) fn completed(&mut self, events: &mut Vec<WebViewEvent>) {
+        if self.pending() == 0 {
+            return;
+        }
+        self.completed += 1;
+        self.report(events);
+    }
+
+    /// Report the fraction of subresources loaded after the DOM is ready.
+    pub(crate) fn subresources(&mut self, fraction: f32, events: &mut Vec<WebViewEvent>) {
+        if self.phase == Phase::DomReady {
+            self.progress(0.5 + 0.5 * fraction, events);
+        }
+    }
+
+    /// Report the overall progress fraction. Ignored outside a load and
+    /// when it would not move forward, so listeners never see it go back.
+    pub(crate) fn progress(&mut self, fraction: f32, events: &mut Vec<WebViewEvent>) {
+        let fraction = fraction.clamp(0.0, 1.0);
+        if !self.is_loading() || fraction <= self.progress {
+            return;
+        }
+        self.progress = fraction;
+        events.push(WebViewEvent::LoadProgress(fraction));
+    }
+
+    /// The load completed. Emits the missing milestones first, so listeners
+    /// always see the full sequence.
+    pub(crate) fn finish(&mut self, events: &mut Vec<WebViewEvent>) {
+        if !self.is_loading() {
+            return;
+        }
+        if self.phase == Phase::Started {
+            self.commit(events);
+        }
+        self.dom_ready(events);
+        self.progress(1.0, events);
+        self.phase = Phase::Idle;
+        events.push(WebViewEvent::LoadFinished);
+    }
+
+    fn report(&mut self, events: &mut Vec<WebViewEvent>) {
+        let fraction = if self.requested == 0 {
+            0.0
+        } else {
+            self.completed as f32 / self.requested as f32
+        };
+        self.subresources(fraction, events);
+    }
+}
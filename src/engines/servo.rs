@@ -1,32 +1,43 @@
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::sync::{Arc, Mutex};
 
 use iced::keyboard;
 use iced::mouse::{self, Interaction};
 use iced::{Point, Size};
 use rand::Rng;
 
-use super::{Engine, PageType, PixelFormat, ViewId};
-use crate::ImageInfo;
+use super::load::LoadTracker;
+use super::{Capabilities, Engine, PageType, PixelFormat, ViewId};
+use crate::{EngineEvent, ImageInfo, ScriptId, ScriptValue, Waker, WebViewError, WebViewEvent};
 
 use dpi::PhysicalSize;
 use servo::{
-    Cursor, InputEvent, KeyboardEvent, MouseButton as ServoMouseButton, MouseButtonAction,
-    MouseButtonEvent, MouseMoveEvent, RenderingContext, Servo as ServoInstance, ServoBuilder,
-    SoftwareRenderingContext, WebView, WebViewBuilder, WebViewDelegate, WheelDelta, WheelEvent,
-    WheelMode,
+    Cursor, InputEvent, JSValue, KeyboardEvent, LoadStatus, MouseButton as ServoMouseButton,
+    MouseButtonAction, MouseButtonEvent, MouseMoveEvent, RenderingContext, Servo as ServoInstance,
+    ServoBuilder, SoftwareRenderingContext, WebView, WebViewBuilder, WebViewDelegate, WheelDelta,
+    WheelEvent, WheelMode,
 };
 use servo::{
     DeviceIndependentPixel, DeviceIntRect, DeviceIntSize, DevicePixel, DevicePoint, WebViewPoint,
 };
 use url::Url;
 
-/// No-op waker — the iced subscription tick already drives `spin_event_loop`.
-struct NoOpWaker;
+/// Forwards Servo's event loop wakeups to the widget's [`Waker`], so
+/// `spin_event_loop` runs as soon as Servo has work instead of on a timer.
+/// The slot is filled by `set_waker` after Servo is built.
+#[derive(Clone, Default)]
+struct EventLoopWaker(Arc<Mutex<Option<Waker>>>);
 
-impl servo::EventLoopWaker for NoOpWaker {
+impl servo::EventLoopWaker for EventLoopWaker {
     fn clone_box(&self) -> Box<dyn servo::EventLoopWaker> {
-        Box::new(NoOpWaker)
+        Box::new(self.clone())
+    }
+
+    fn wake(&self) {
+        if let Some(waker) = self.0.lock().unwrap().as_ref() {
+            waker.wake();
+        }
     }
 }
 
@@ -37,6 +48,10 @@ struct DelegateState {
     title: RefCell<Option<String>>,
     cursor: RefCell<Cursor>,
     frame_ready: RefCell<bool>,
+    /// Load milestones in the order Servo reported them.
+    load_status: RefCell<Vec<LoadStatus>>,
+    /// Results of `evaluate_script`, in the order they arrived.
+    script_results: RefCell<Vec<(ScriptId, Result<ScriptValue, WebViewError>)>>,
 }
 
 /// Per-webview delegate that writes into a shared `DelegateState`.
@@ -60,6 +75,10 @@ impl WebViewDelegate for ViewDelegate {
     fn notify_new_frame_ready(&self, _webview: WebView) {
         *self.state.frame_ready.borrow_mut() = true;
     }
+
+    fn notify_load_status_changed(&self, _webview: WebView, status: LoadStatus) {
+        self.state.load_status.borrow_mut().push(status);
+    }
 }
 
 struct ServoView {
@@ -73,6 +92,9 @@ struct ServoView {
     needs_render: bool,
     size: Size<u32>,
     last_cursor: DevicePoint,
+    load: LoadTracker,
+    /// Events not yet drained by `take_events`.
+    events: Vec<WebViewEvent>,
 }
 
 /// Full browser engine backed by [Servo](https://servo.org/) (HTML5, CSS3, JS).
@@ -94,6 +116,7 @@ pub struct Servo {
     rendering_context: Rc<SoftwareRenderingContext>,
     views: Vec<ServoView>,
     scale_factor: f32,
+    waker: EventLoopWaker,
 }
 
 impl Default for Servo {
@@ -103,8 +126,9 @@ impl Default for Servo {
             SoftwareRenderingContext::new(size).expect("failed to create SoftwareRenderingContext");
         let rendering_context = Rc::new(rendering_context);
 
+        let waker = EventLoopWaker::default();
         let instance = ServoBuilder::default()
-            .event_loop_waker(Box::new(NoOpWaker))
+            .event_loop_waker(Box::new(waker.clone()))
             .build();
 
         Self {
@@ -112,6 +136,7 @@ impl Default for Servo {
             rendering_context,
             views: Vec::new(),
             scale_factor: 1.0,
+            waker,
         }
     }
 }
@@ -126,6 +151,26 @@ impl Servo {
     }
 }
 
+fn js_to_script_value(value: JSValue) -> ScriptValue {
+    match value {
+        JSValue::Undefined => ScriptValue::Undefined,
+        JSValue::Null => ScriptValue::Null,
+        JSValue::Boolean(b) => ScriptValue::Bool(b),
+        JSValue::Number(n) => ScriptValue::Number(n),
+        JSValue::String(s) => ScriptValue::String(s),
+        JSValue::Array(items) => {
+            ScriptValue::Array(items.into_iter().map(js_to_script_value).collect())
+        }
+        JSValue::Object(map) => ScriptValue::Object(
+            map.into_iter()
+                .map(|(k, v)| (k, js_to_script_value(v)))
+                .collect(),
+        ),
+        // Elements, frames and windows come back as opaque ids.
+        _ => ScriptValue::Object(Default::default()),
+    }
+}
+
 fn cursor_to_interaction(cursor: Cursor) -> Interaction {
     match cursor {
         Cursor::Pointer => Interaction::Pointer,
@@ -158,9 +203,14 @@ fn capture_frame(view: &mut ServoView, rendering_context: &SoftwareRenderingCont
 
     let rect = DeviceIntRect::from_size(DeviceIntSize::new(w as i32, h as i32));
 
-    if let Some(image_buf) = rendering_context.read_to_image(rect) {
-        let pixels = image_buf.into_raw();
-        view.last_frame = ImageInfo::new(pixels, PixelFormat::Rgba, w, h);
+    match rendering_context.read_to_image(rect) {
+        Some(image_buf) => {
+            let pixels = image_buf.into_raw();
+            view.last_frame = ImageInfo::new(pixels, PixelFormat::Rgba, w, h);
+        }
+        None => view.events.push(WebViewEvent::Error(WebViewError::Render(
+            "failed to read back frame".to_string(),
+        ))),
     }
 
     view.needs_render = false;
@@ -171,20 +221,52 @@ impl Engine for Servo {
         true
     }
 
+    fn capabilities(&self) -> Capabilities {
+        // Selection is engine-managed and not queryable, see the type docs.
+        Capabilities {
+            javascript: true,
+            history: true,
+            keyboard_input: true,
+            text_selection: false,
+            native_urls: true,
+        }
+    }
+
     fn update(&mut self) {
         self.instance.spin_event_loop();
 
         for view in &mut self.views {
             // Drain delegate state
             if let Some(url) = view.delegate_state.url.borrow_mut().take() {
-                view.url = url;
+                if url != view.url {
+                    view.url = url.clone();
+                    view.events.push(WebViewEvent::UrlChanged(url));
+                }
             }
             if let Some(title) = view.delegate_state.title.borrow_mut().take() {
-                view.title = title;
+                if title != view.title {
+                    view.title = title.clone();
+                    view.events.push(WebViewEvent::TitleChanged(title));
+                }
             }
             {
-                let cursor = *view.delegate_state.cursor.borrow();
-                view.cursor = cursor_to_interaction(cursor);
+                let cursor = cursor_to_interaction(*view.delegate_state.cursor.borrow());
+                if cursor != view.cursor {
+                    view.cursor = cursor;
+                    view.events.push(WebViewEvent::CursorChanged(cursor));
+                }
+            }
+            for status in view.delegate_state.load_status.take() {
+                match status {
+                    LoadStatus::Started => view.load.start(&mut view.events),
+                    LoadStatus::HeadParsed => view.load.commit(&mut view.events),
+                    // Servo has no separate DOMContentLoaded milestone
+                    LoadStatus::Complete => view.load.finish(&mut view.events),
+                }
+            }
+            for (script_id, result) in view.delegate_state.script_results.take() {
+                view.events
+                    .push(WebViewEvent::ScriptResult(script_id, result));
             }
             if view.delegate_state.frame_ready.replace(false) {
                 view.needs_render = true;
@@ -192,6 +274,15 @@ impl Engine for Servo {
         }
     }
 
+    fn set_waker(&mut self, waker: Waker) {
+        *self.waker.0.lock().unwrap() = Some(waker);
+    }
+
+    fn needs_update(&self) -> bool {
+        // Everything else arrives through the event loop waker.
+        self.views.iter().any(|view| view.needs_render)
+    }
+
     fn render(&mut self, _size: Size<u32>) {
         for i in 0..self.views.len() {
             if self.views[i].needs_render {
@@ -222,14 +313,28 @@ impl Engine for Servo {
             title: RefCell::new(None),
             cursor: RefCell::new(Cursor::Default),
             frame_ready: RefCell::new(false),
+            load_status: RefCell::new(Vec::new()),
+            script_results: RefCell::new(Vec::new()),
         });
 
         let delegate = Rc::new(ViewDelegate {
             state: Rc::clone(&delegate_state),
         });
 
+        let mut events = Vec::new();
         let (url_str, initial_url) = match &content {
-            Some(PageType::Url(u)) => (u.clone(), Url::parse(u).ok()),
+            Some(PageType::Url(u)) => match Url::parse(u) {
+                Ok(parsed) => {
+                    events.push(WebViewEvent::UrlChanged(u.clone()));
+                    (u.clone(), Some(parsed))
+                }
+                Err(e) => {
+                    events.push(WebViewEvent::Error(WebViewError::InvalidUrl(format!(
+                        "{u}: {e}"
+                    ))));
+                    (u.clone(), None)
+                }
+            },
             Some(PageType::Html(html)) => {
                 let data_url =
                     format!("data:text/html;charset=utf-8,{}", urlencoding::encode(html));
@@ -264,11 +369,21 @@ impl Engine for Servo {
             needs_render: true,
             size,
             last_cursor: DevicePoint::new(w as f32 / 2.0, h as f32 / 2.0),
+            load: LoadTracker::default(),
+            events,
         };
         self.views.push(view);
         id
     }
 
+    fn take_events(&mut self) -> Vec<EngineEvent> {
+        let mut result = Vec::new();
+        for view in &mut self.views {
+            result.extend(view.events.drain(..).map(|e| EngineEvent::new(view.id, e)));
+        }
+        result
+    }
+
     fn remove_view(&mut self, id: ViewId) {
         self.views.retain(|v| v.id != id);
     }
@@ -395,12 +510,20 @@ impl Engine for Servo {
             return;
         };
         match page_type {
-            PageType::Url(url) => {
-                if let Ok(parsed) = Url::parse(&url) {
-                    view.url = url;
+            PageType::Url(url) => match Url::parse(&url) {
+                Ok(parsed) => {
+                    if url != view.url {
+                        view.url = url.clone();
+                        view.events.push(WebViewEvent::UrlChanged(url));
+                    }
                     view.webview.load(parsed);
                 }
-            }
+                Err(e) => view
+                    .events
+                    .push(WebViewEvent::Error(WebViewError::InvalidUrl(format!(
+                        "{url}: {e}"
+                    )))),
+            },
             PageType::Html(html) => {
                 let data_url = format!(
                     "data:text/html;charset=utf-8,{}",
@@ -431,6 +554,39 @@ impl Engine for Servo {
         }
     }
 
+    fn evaluate_script(
+        &mut self,
+        id: ViewId,
+        script_id: ScriptId,
+        script: String,
+    ) -> Result<(), WebViewError> {
+        let view = self
+            .find_view(id)
+            .ok_or_else(|| WebViewError::Script(format!("no view with id {id}")))?;
+        let state = Rc::clone(&view.delegate_state);
+        view.webview.evaluate_javascript(script, move |result| {
+            let result = result
+                .map(js_to_script_value)
+                .map_err(|e| WebViewError::Script(format!("{e:?}")));
+            state.script_results.borrow_mut().push((script_id, result));
+        });
+        Ok(())
+    }
+
+    fn can_go_back(&self, id: ViewId) -> bool {
+        self.find_view(id).is_some_and(|v| v.webview.can_go_back())
+    }
+
+    fn can_go_forward(&self, id: ViewId) -> bool {
+        self.find_view(id)
+            .is_some_and(|v| v.webview.can_go_forward())
+    }
+
+    fn is_loading(&self, id: ViewId) -> bool {
+        self.find_view(id)
+            .is_some_and(|v| v.webview.load_status() != LoadStatus::Complete)
+    }
+
     fn get_url(&self, id: ViewId) -> String {
         let Some(view) = self.find_view(id) else {
             return "about:blank".to_string();
@@ -0,0 +1,433 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::time::{Duration, Instant};
+
+use iced::Rectangle;
+
+use crate::{ImageInfo, PixelFormat, Tile};
+
+/// Height of one tile in logical pixels.
+const TILE_HEIGHT: f32 = 1024.0;
+
+/// Tiles kept per view, visible ones included. Bounds memory to about
+/// `MAX_TILES * TILE_HEIGHT` rows whatever the document height.
+const MAX_TILES: usize = 8;
+
+/// How far beyond the viewport tiles are rasterized ahead of scrolling.
+const PREFETCH_MARGIN: f32 = TILE_HEIGHT / 2.0;
+
+/// Default minimum time between two `:hover` repaints.
+pub(crate) const HOVER_THROTTLE: Duration = Duration::from_millis(50);
+
+struct CachedTile {
+    index: u32,
+    tile: Tile,
+    /// Hash of the pixels, so a redraw with identical output keeps the old
+    /// image handle and avoids a texture re-upload.
+    hash: u64,
+    /// Drawn before the last `invalidate`.
+    stale: bool,
+    last_used: u64,
+}
+
+/// Rasterizes a document in fixed-height tiles around the scroll position
+/// and keeps the most recently used ones.
+///
+/// Tiles are shown through image handles unless shader rendering is on, so
+/// their handles are built as they are drawn: on the engine's thread, which
+/// is the worker under [`EngineHost`](crate::EngineHost), not in `view`.
+#[derive(Default)]
+pub(crate) struct TileCache {
+    /// Layout of the pixels the engine draws.
+    format: PixelFormat,
+    tiles: Vec<CachedTile>,
+    /// Tiles intersecting the viewport, top to bottom.
+    visible: Vec<Tile>,
+    clock: u64,
+}
+
+impl TileCache {
+    /// A cache for an engine that draws pixels in `format`.
+    pub(crate) fn new(format: PixelFormat) -> Self {
+        Self {
+            format,
+            ..Self::default()
+        }
+    }
+
+    /// The content changed: every tile must be redrawn before it is shown
+    /// again.
+    pub(crate) fn invalidate(&mut self) {
+        for cached in &mut self.tiles {
+            cached.stale = true;
+        }
+    }
+
+    /// Drop every tile, e.g. when there is no document any more.
+    pub(crate) fn clear(&mut self) {
+        self.tiles.clear();
+        self.visible.clear();
+    }
+
+    /// Tiles intersecting the viewport after the last `update`, top to bottom.
+    pub(crate) fn visible(&self) -> &[Tile] {
+        &self.visible
+    }
+
+    /// Make sure the tiles around `scroll_y` are drawn, evicting the least
+    /// recently used ones beyond `MAX_TILES`.
+    ///
+    /// `draw(top, height)` rasterizes that band of the document and returns
+    /// its pixels, in the cache's format, with their physical width and
+    /// height.
+    /// Returns whether any visible tile changed.
+    pub(crate) fn update(
+        &mut self,
+        scroll_y: f32,
+        viewport_height: f32,
+        document_height: f32,
+        mut draw: impl FnMut(f32, f32) -> (Vec<u8>, u32, u32),
+    ) -> bool {
+        self.clock += 1;
+        let document_height = document_height.max(viewport_height);
+        let last = tile_index(document_height - 1.0);
+        let wanted = tile_index(scroll_y - PREFETCH_MARGIN)
+            ..=tile_index(scroll_y + viewport_height + PREFETCH_MARGIN).min(last);
+        let shown = tile_index(scroll_y)..=tile_index(scroll_y + viewport_height - 1.0).min(last);
+
+        // Stale tiles out of reach would only be redrawn if scrolled to
+        self.tiles
+            .retain(|cached| !cached.stale || wanted.contains(&cached.index));
+
+        let mut changed = false;
+        for index in wanted.clone() {
+            let top = index as f32 * TILE_HEIGHT;
+            let height = TILE_HEIGHT.min(document_height - top);
+            let position = self.tiles.iter().position(|cached| cached.index == index);
+            let current = position.map(|i| &self.tiles[i]);
+            if current.is_some_and(|cached| !cached.stale && cached.tile.height == height) {
+                self.tiles[position.unwrap()].last_used = self.clock;
+                continue;
+            }
+
+            let (pixels, width, physical_height) = draw(top, height);
+            let hash = hash_pixels(&pixels, width, physical_height);
+
+            let tile = match current {
+                Some(cached) if cached.hash == hash && cached.tile.height == height => {
+                    cached.tile.clone()
+                }
+                _ => {
+                    changed |= shown.contains(&index);
+                    let image =
+                        ImageInfo::new(pixels, self.format, width, physical_height).with_handle();
+                    Tile::new(top, height, image)
+                }
+            };
+            let cached = CachedTile {
+                index,
+                tile,
+                hash,
+                stale: false,
+                last_used: self.clock,
+            };
+            match position {
+                Some(i) => self.tiles[i] = cached,
+                None => self.tiles.push(cached),
+            }
+        }
+
+        // Evict least recently used first; tiles in use this round are newest.
+        if self.tiles.len() > MAX_TILES {
+            self.tiles
+                .sort_by_key(|cached| std::cmp::Reverse(cached.last_used));
+            self.tiles.truncate(MAX_TILES.max(wanted.clone().count()));
+        }
+
+        let visible: Vec<Tile> = shown
+            .filter_map(|index| self.tiles.iter().find(|cached| cached.index == index))
+            .map(|cached| cached.tile.clone())
+            .collect();
+        changed |= visible
+            .iter()
+            .map(Tile::top)
+            .ne(self.visible.iter().map(Tile::top));
+        self.visible = visible;
+        changed
+    }
+
+    /// Redraw `[x, y, width, height]` (logical document coordinates) in every
+    /// cached tile it touches, leaving the rest of each tile as it is.
+    ///
+    /// `draw(x, y, width, height)` rasterizes that rectangle like `update`'s
+    /// `draw`. Patched tiles carry the patch as damage, so the shader widget
+    /// uploads only that region.
+    /// Returns whether any visible tile changed.
+    pub(crate) fn patch(
+        &mut self,
+        rect: [f32; 4],
+        mut draw: impl FnMut(f32, f32, f32, f32) -> (Vec<u8>, u32, u32),
+    ) -> bool {
+        let mut changed = false;
+        for cached in self.tiles.iter_mut().filter(|cached| !cached.stale) {
+            let tile = &cached.tile;
+            let (width, height) = (tile.image.image_width(), tile.image.image_height());
+            // Physical pixels per logical pixel
+            let k = height as f32 / tile.height;
+
+            let x0 = rect[0].max(0.0).floor();
+            let x1 = (rect[0] + rect[2]).min(width as f32 / k).ceil();
+            let y0 = rect[1].max(tile.top).floor();
+            let y1 = (rect[1] + rect[3]).min(tile.top + tile.height).ceil();
+            if x1 <= x0 || y1 <= y0 {
+                continue;
+            }
+
+            let (patch, patch_w, patch_h) = draw(x0, y0, x1 - x0, y1 - y0);
+            let col = ((x0 * k).round() as u32).min(width);
+            let row = (((y0 - tile.top) * k).round() as u32).min(height);
+            let cols = patch_w.min(width - col) as usize;
+            let rows = patch_h.min(height - row) as usize;
+
+            let mut pixels = tile.image.raw_pixels().to_vec();
+            let stride = width as usize * 4;
+            for (i, src) in patch
+                .chunks_exact(patch_w as usize * 4)
+                .take(rows)
+                .enumerate()
+            {
+                let start = (row as usize + i) * stride + col as usize * 4;
+                pixels[start..start + cols * 4].copy_from_slice(&src[..cols * 4]);
+            }
+
+            let hash = hash_pixels(&pixels, width, height);
+            if hash == cached.hash {
+                continue;
+            }
+            // Only the patch differs, so the texture can take just that.
+            let damage = Rectangle {
+                x: col,
+                y: row,
+                width: cols as u32,
+                height: rows as u32,
+            };
+            cached.hash = hash;
+            cached.tile.image = ImageInfo::new(pixels, self.format, width, height)
+                .with_damage(&cached.tile.image, vec![damage])
+                .with_handle();
+
+            if let Some(shown) = self
+                .visible
+                .iter_mut()
+                .find(|shown| shown.top == cached.tile.top)
+            {
+                *shown = cached.tile.clone();
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+/// Regions a `:hover` change made dirty, waiting to be patched into the
+/// tiles at most once per throttle interval.
+#[derive(Default)]
+pub(crate) struct HoverDamage {
+    /// `[x, y, width, height]` in logical document coordinates.
+    rects: Vec<[f32; 4]>,
+    /// A hover change is waiting, even if none of its rects are known yet.
+    pending: bool,
+    last_repaint: Option<Instant>,
+}
+
+impl HoverDamage {
+    /// Record a hover change, with the region it dirtied if known.
+    pub(crate) fn add(&mut self, rect: Option<[f32; 4]>) {
+        self.pending = true;
+        self.rects.extend(rect.filter(|r| r[2] > 0.0 && r[3] > 0.0));
+    }
+
+    pub(crate) fn is_pending(&self) -> bool {
+        self.pending
+    }
+
+    /// Forget the damage, e.g. because the whole document is redrawn anyway.
+    pub(crate) fn clear(&mut self) {
+        self.rects.clear();
+        self.pending = false;
+    }
+
+    /// The dirty rects, once `throttle` has passed since the last repaint.
+    pub(crate) fn take_due(&mut self, throttle: Duration) -> Option<Vec<[f32; 4]>> {
+        if !self.pending || self.last_repaint.is_some_and(|at| at.elapsed() < throttle) {
+            return None;
+        }
+        self.pending = false;
+        self.last_repaint = Some(Instant::now());
+        let mut rects = std::mem::take(&mut self.rects);
+        rects.dedup();
+        Some(rects)
+    }
+}
+
+fn hash_pixels(pixels: &[u8], width: u32, height: u32) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    pixels.hash(&mut hasher);
+    (width, height).hash(&mut hasher);
+    hasher.finish()
+}
+
+fn tile_index(y: f32) -> u32 {
+    (y.max(0.0) / TILE_HEIGHT) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIDTH: u32 = 100;
+
+    /// Draws every band in one grey `level`, at `scale` physical pixels per
+    /// logical pixel.
+    fn fill(level: u8, scale: f32) -> impl FnMut(f32, f32) -> (Vec<u8>, u32, u32) {
+        move |_, height| {
+            let (w, h) = (WIDTH * scale as u32, (height * scale).ceil() as u32);
+            (vec![level; (w * h * 4) as usize], w, h)
+        }
+    }
+
+    fn patch_fill(level: u8, scale: f32) -> impl FnMut(f32, f32, f32, f32) -> (Vec<u8>, u32, u32) {
+        move |_, _, width, height| {
+            let w = (width * scale).ceil() as u32;
+            let h = (height * scale).ceil() as u32;
+            (vec![level; (w * h * 4) as usize], w, h)
+        }
+    }
+
+    fn level_at(tile: &Tile, x: u32, y: u32) -> u8 {
+        tile.image.raw_pixels()[((y * tile.image.image_width() + x) * 4) as usize]
+    }
+
+    #[test]
+    fn update_draws_the_tiles_around_the_viewport() {
+        let mut cache = TileCache::new(PixelFormat::Rgba);
+        assert!(cache.update(0.0, 600.0, 5000.0, fill(0, 1.0)));
+
+        let tops: Vec<f32> = cache.visible().iter().map(Tile::top).collect();
+        assert_eq!(tops, [0.0]);
+        // The prefetch margin reaches into the second tile
+        assert_eq!(cache.tiles.len(), 2);
+
+        // Nothing to draw the second time round
+        let mut drawn = 0;
+        let changed = cache.update(0.0, 600.0, 5000.0, |top, height| {
+            drawn += 1;
+            fill(0, 1.0)(top, height)
+        });
+        assert!(!changed);
+        assert_eq!(drawn, 0);
+    }
+
+    #[test]
+    fn tiles_carry_their_image_handle() {
+        let mut cache = TileCache::new(PixelFormat::RgbaPremultiplied);
+        cache.update(0.0, 600.0, 5000.0, fill(0, 1.0));
+        assert!(cache.visible()[0].image.handle.get().is_some());
+
+        cache.patch([0.0, 0.0, 10.0, 10.0], patch_fill(255, 1.0));
+        assert!(cache.visible()[0].image.handle.get().is_some());
+    }
+
+    #[test]
+    fn patch_redraws_only_the_rect_and_reports_it_as_damage() {
+        let mut cache = TileCache::new(PixelFormat::Rgba);
+        cache.update(0.0, 600.0, 5000.0, fill(0, 2.0));
+        let before = cache.visible()[0].image.clone();
+
+        assert!(cache.patch([10.0, 20.0, 30.0, 40.0], patch_fill(255, 2.0)));
+
+        let tile = &cache.visible()[0];
+        assert_eq!(level_at(tile, 20, 40), 255);
+        assert_eq!(level_at(tile, 79, 119), 255);
+        assert_eq!(level_at(tile, 19, 40), 0);
+        assert_eq!(level_at(tile, 80, 120), 0);
+        assert_eq!(
+            tile.image.damage_since(before.frame()),
+            Some(
+                &[Rectangle {
+                    x: 20,
+                    y: 40,
+                    width: 60,
+                    height: 80,
+                }][..]
+            )
+        );
+    }
+
+    #[test]
+    fn patch_lands_in_every_tile_it_touches() {
+        let mut cache = TileCache::new(PixelFormat::Rgba);
+        cache.update(900.0, 600.0, 5000.0, fill(0, 1.0));
+        assert_eq!(cache.visible().len(), 2);
+
+        // Straddles the boundary at 1024
+        assert!(cache.patch([0.0, 1000.0, 10.0, 50.0], patch_fill(255, 1.0)));
+
+        let [first, second] = cache.visible() else {
+            panic!("expected two visible tiles");
+        };
+        assert_eq!(level_at(first, 0, 1023), 255);
+        assert_eq!(level_at(first, 0, 999), 0);
+        assert_eq!(level_at(second, 0, 25), 255);
+        assert_eq!(level_at(second, 0, 26), 0);
+    }
+
+    #[test]
+    fn identical_patch_keeps_the_tile() {
+        let mut cache = TileCache::new(PixelFormat::Rgba);
+        cache.update(0.0, 600.0, 5000.0, fill(0, 1.0));
+        let frame = cache.visible()[0].image.frame();
+
+        assert!(!cache.patch([0.0, 0.0, 50.0, 50.0], patch_fill(0, 1.0)));
+        assert_eq!(cache.visible()[0].image.frame(), frame);
+    }
+
+    #[test]
+    fn patch_skips_stale_tiles_and_rects_outside_the_cache() {
+        let mut cache = TileCache::new(PixelFormat::Rgba);
+        cache.update(0.0, 600.0, 5000.0, fill(0, 1.0));
+
+        let mut drawn = 0;
+        let mut draw = |x, y, w, h| {
+            drawn += 1;
+            patch_fill(255, 1.0)(x, y, w, h)
+        };
+        assert!(!cache.patch([0.0, 4000.0, 10.0, 10.0], &mut draw));
+        cache.invalidate();
+        assert!(!cache.patch([0.0, 0.0, 10.0, 10.0], &mut draw));
+        assert_eq!(drawn, 0);
+    }
+
+    #[test]
+    fn hover_damage_is_throttled() {
+        let mut hover = HoverDamage::default();
+        assert!(hover.take_due(Duration::ZERO).is_none());
+
+        hover.add(Some([0.0, 0.0, 10.0, 10.0]));
+        hover.add(Some([0.0, 0.0, 10.0, 10.0]));
+        hover.add(Some([0.0, 0.0, 0.0, 10.0]));
+        assert_eq!(
+            hover.take_due(Duration::from_secs(60)),
+            Some(vec![[0.0, 0.0, 10.0, 10.0]])
+        );
+
+        // Inside the throttle interval the damage waits
+        hover.add(None);
+        assert!(hover.is_pending());
+        assert!(hover.take_due(Duration::from_secs(60)).is_none());
+        assert_eq!(hover.take_due(Duration::ZERO), Some(Vec::new()));
+
+        hover.add(Some([1.0, 2.0, 3.0, 4.0]));
+        hover.clear();
+        assert!(!hover.is_pending());
+    }
+}
@@ -0,0 +1,93 @@
+use std::fmt;
+
+/// Errors reported by the webview widgets and engines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WebViewError {
+    /// The URL could not be parsed or resolved.
+    InvalidUrl(String),
+    /// DNS, connect or transport failure, including a connection dropped mid-response.
+    Network(String),
+    /// The request timed out.
+    Timeout,
+    /// The server answered 502, 503 or 504, and retries were exhausted.
+    /// Other statuses are rendered like any response.
+    HttpStatus(u16),
+    /// The response exceeded the size limit for its kind.
+    SizeLimit {
+        /// Size of the response in bytes.
+        size: u64,
+        /// Limit that was exceeded, in bytes.
+        limit: u64,
+    },
+    /// The URL is missing from the [`FetchMode::Replay`](crate::FetchMode::Replay)
+    /// archive, or the archive entry could not be read.
+    NotInArchive(String),
+    /// A response could not be written to the
+    /// [`FetchMode::Record`](crate::FetchMode::Record) archive.
+    Record(String),
+    /// A fetched resource could not be decoded, e.g. a corrupt image.
+    Decode(String),
+    /// An image could not be fetched; `error` says why.
+    Image {
+        /// The image URL as the document references it.
+        url: String,
+        /// What went wrong fetching it.
+        error: Box<WebViewError>,
+    },
+    /// The engine failed to parse the document.
+    Parse(String),
+    /// The engine failed to produce a frame.
+    Render(String),
+    /// A script passed to `EvaluateScript` threw, or its result could not
+    /// be sent back.
+    Script(String),
+    /// The engine does not support the requested feature, e.g. running
+    /// scripts without a JavaScript engine.
+    Unsupported(String),
+}
+
+impl WebViewError {
+    /// Whether retrying the same request might succeed: timeouts, network
+    /// failures and 502/503/504 responses.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            WebViewError::Timeout | WebViewError::Network(_) | WebViewError::HttpStatus(502..=504)
+        ) || matches!(self, WebViewError::Image { error, .. } if error.is_transient())
+    }
+}
+
+impl fmt::Display for WebViewError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WebViewError::InvalidUrl(e) => write!(f, "invalid URL: {e}"),
+            WebViewError::Network(e) => write!(f, "network error: {e}"),
+            WebViewError::Timeout => write!(f, "request timed out"),
+            WebViewError::HttpStatus(code) => write!(f, "server responded with HTTP {code}"),
+            WebViewError::SizeLimit { size, limit } => {
+                write!(
+                    f,
+                    "response too large: {size} bytes exceeds {limit} byte limit"
+                )
+            }
+            WebViewError::NotInArchive(e) => write!(f, "not in replay archive: {e}"),
+            WebViewError::Record(e) => write!(f, "failed to record: {e}"),
+            WebViewError::Decode(e) => write!(f, "failed to decode: {e}"),
+            WebViewError::Image { url, error } => write!(f, "failed to load image {url}: {error}"),
+            WebViewError::Parse(e) => write!(f, "failed to parse document: {e}"),
+            WebViewError::Render(e) => write!(f, "failed to render: {e}"),
+            WebViewError::Script(e) => write!(f, "script failed: {e}"),
+            WebViewError::Unsupported(e) => write!(f, "not supported by this engine: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for WebViewError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WebViewError::Image { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
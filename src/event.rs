@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+
+use iced::mouse::Interaction;
+use iced::Size;
+
+use crate::{ViewId, WebViewError};
+
+/// A state change in a view, pushed by the engine as it happens.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum WebViewEvent {
+    /// The view navigated to a new URL.
+    UrlChanged(String),
+    /// The document title changed.
+    TitleChanged(String),
+    /// The mouse cursor requested by the page changed.
+    CursorChanged(Interaction),
+    /// The laid-out document size changed, in logical pixels.
+    ContentSizeChanged(Size<f32>),
+    /// The text selection changed. Empty when the selection was cleared.
+    SelectionChanged(String),
+    /// The page announced a new favicon URL.
+    FaviconChanged(String),
+    /// A navigation began; the previous page is still shown.
+    LoadStarted,
+    /// The new document replaced the previous one.
+    LoadCommitted,
+    /// The document is parsed and laid out; subresources may still be loading.
+    DomReady,
+    /// Fraction of the load completed, from `0.0` to `1.0`.
+    LoadProgress(f32),
+    /// The document and all of its subresources have loaded.
+    LoadFinished,
+    /// Something went wrong; also delivered through `on_error`.
+    Error(WebViewError),
+    /// The script sent with `EvaluateScript` under this id finished; also
+    /// delivered through `on_script_result`.
+    ScriptResult(ScriptId, Result<ScriptValue, WebViewError>),
+}
+
+/// Chosen by the caller of `EvaluateScript` and handed back with the result,
+/// so results can be matched to their scripts.
+pub type ScriptId = u64;
+
+/// The value a script evaluated to, as JSON sees it.
+///
+/// CEF waits for a promise to settle and reports its value; Servo reports a
+/// promise like any other object. Host objects such as DOM nodes have no
+/// JSON form; engines report them as empty objects.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptValue {
+    /// `undefined`, e.g. the value of a statement list ending in a declaration.
+    Undefined,
+    /// `null`
+    Null,
+    /// A boolean.
+    Bool(bool),
+    /// A number.
+    Number(f64),
+    /// A string.
+    String(String),
+    /// An array.
+    Array(Vec<ScriptValue>),
+    /// A plain object, by property name.
+    Object(HashMap<String, ScriptValue>),
+}
+
+/// A [`WebViewEvent`] tagged with the view it belongs to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngineEvent {
+    /// The view the event happened in.
+    pub view: ViewId,
+    /// What happened.
+    pub event: WebViewEvent,
+}
+
+impl EngineEvent {
+    /// Tag `event` with `view`.
+    pub fn new(view: ViewId, event: WebViewEvent) -> Self {
+        Self { view, event }
+    }
+}
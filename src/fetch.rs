@@ -1,7 +1,13 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::LazyLock;
+use std::time::Duration;
 use url::Url;
 
+use crate::WebViewError;
+
+mod archive;
+
 /// Max response size for the main page (10 MB).
 const MAX_PAGE_SIZE: u64 = 10 * 1024 * 1024;
 /// Max response size for a single image (10 MB).
@@ -13,13 +19,96 @@ const MAX_STYLESHEETS: usize = 50;
 /// Max depth for @import chains to prevent infinite loops.
 const MAX_IMPORT_DEPTH: usize = 3;
 
+/// Runs fetches started outside the widgets' tasks, i.e. Blitz sub-resource
+/// loads issued from inside the engine, on a thread of their own.
+#[cfg(feature = "blitz")]
+static RUNTIME: LazyLock<tokio::runtime::Handle> = LazyLock::new(|| {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to build fetch runtime");
+    let handle = runtime.handle().clone();
+    std::thread::Builder::new()
+        .name("iced_webview-fetch".into())
+        .spawn(move || runtime.block_on(std::future::pending::<()>()))
+        .expect("failed to spawn fetch thread");
+    handle
+});
+
 static HTTP_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
     reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(30))
+        .timeout(Duration::from_secs(30))
         .build()
         .expect("failed to build HTTP client")
 });
 
+/// Where page, stylesheet and image fetches are served from.
+///
+/// Only applies to engines without native URL support (litehtml, Blitz).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum FetchMode {
+    /// Fetch everything from the network.
+    #[default]
+    Network,
+    /// Fetch from the network and write every response body into the given
+    /// directory, so the site can be replayed later. A response that can't
+    /// be written fails its fetch with [`WebViewError::Record`].
+    Record(PathBuf),
+    /// Serve responses only from a directory written by [`FetchMode::Record`].
+    /// URLs missing from the archive fail instead of touching the network.
+    Replay(PathBuf),
+}
+
+/// How often and how patiently transient fetch failures are retried.
+///
+/// Only failures where [`WebViewError::is_transient`] holds are retried — timeouts,
+/// connection failures and 502/503/504 responses. The delay before retry `n`
+/// is `initial_backoff * multiplier^n`, capped at `max_backoff`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt. `0` disables retrying.
+    pub max_retries: u32,
+    /// Delay before the first retry.
+    pub initial_backoff: Duration,
+    /// Upper bound for any single delay.
+    pub max_backoff: Duration,
+    /// Factor the delay grows by after each retry.
+    pub multiplier: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(5),
+            multiplier: 2.0,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Never retry; every failure is reported immediately.
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    fn backoff(&self, retry: u32) -> Duration {
+        let secs = self.initial_backoff.as_secs_f64() * self.multiplier.max(1.0).powi(retry as i32);
+        Duration::from_secs_f64(secs.min(self.max_backoff.as_secs_f64()))
+    }
+}
+
+/// Fetch settings carried by the widgets into every fetch task.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct FetchOptions {
+    pub(crate) mode: FetchMode,
+    pub(crate) retry: RetryPolicy,
+}
+
 /// Fetch a URL and return raw HTML plus a pre-fetched CSS cache.
 ///
 /// The CSS cache maps resolved stylesheet URLs to their CSS text.
@@ -27,31 +116,12 @@ static HTTP_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
 /// `import_css` callback looks up stylesheets from the cache instead.
 pub(crate) async fn fetch_html(
     page_url: String,
-) -> Result<(String, HashMap<String, String>), String> {
+    options: FetchOptions,
+) -> Result<(String, HashMap<String, String>), WebViewError> {
     let client = &*HTTP_CLIENT;
-    let base = Url::parse(&page_url).map_err(|e| e.to_string())?;
+    let base = Url::parse(&page_url).map_err(|e| WebViewError::InvalidUrl(e.to_string()))?;
 
-    let response = client
-        .get(&page_url)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if let Some(len) = response.content_length() {
-        if len > MAX_PAGE_SIZE {
-            return Err(format!(
-                "page too large: {len} bytes exceeds {MAX_PAGE_SIZE} byte limit"
-            ));
-        }
-    }
-
-    let body = response.bytes().await.map_err(|e| e.to_string())?;
-    if body.len() as u64 > MAX_PAGE_SIZE {
-        return Err(format!(
-            "page too large: {} bytes exceeds {MAX_PAGE_SIZE} byte limit",
-            body.len()
-        ));
-    }
+    let body = fetch_bytes(client, &page_url, MAX_PAGE_SIZE, &options).await?;
     let html = String::from_utf8_lossy(&body).into_owned();
 
     // Pre-fetch external stylesheets into a cache keyed by resolved URL.
@@ -63,7 +133,7 @@ pub(crate) async fn fetch_html(
         &links
     };
     for css_url in capped {
-        fetch_css_recursive(client, css_url, &mut css_cache, 0).await;
+        fetch_css_recursive(client, css_url, &mut css_cache, 0, &options).await;
     }
 
     Ok((html, css_cache))
@@ -75,13 +145,14 @@ async fn fetch_css_recursive(
     url: &Url,
     cache: &mut HashMap<String, String>,
     depth: usize,
+    options: &FetchOptions,
 ) {
     let key = url.to_string();
     if cache.contains_key(&key) || depth > MAX_IMPORT_DEPTH {
         return;
     }
 
-    let css = match fetch_css(client, url).await {
+    let css = match fetch_css(client, url, options).await {
         Some(text) => text,
         None => return,
     };
@@ -94,20 +165,22 @@ async fn fetch_css_recursive(
         if cache.len() >= MAX_STYLESHEETS {
             break;
         }
-        Box::pin(fetch_css_recursive(client, &import_url, cache, depth + 1)).await;
+        Box::pin(fetch_css_recursive(
+            client,
+            &import_url,
+            cache,
+            depth + 1,
+            options,
+        ))
+        .await;
     }
 }
 
 /// Fetch a single CSS URL with size limits. Returns None on failure.
-async fn fetch_css(client: &reqwest::Client, url: &Url) -> Option<String> {
-    let resp = client.get(url.clone()).send().await.ok()?;
-    if resp.content_length().is_some_and(|len| len > MAX_CSS_SIZE) {
-        return None;
-    }
-    let bytes = resp.bytes().await.ok()?;
-    if bytes.len() as u64 > MAX_CSS_SIZE {
-        return None;
-    }
+async fn fetch_css(client: &reqwest::Client, url: &Url, options: &FetchOptions) -> Option<String> {
+    let bytes = fetch_bytes(client, url.as_str(), MAX_CSS_SIZE, options)
+        .await
+        .ok()?;
     Some(String::from_utf8_lossy(&bytes).into_owned())
 }
 
@@ -198,29 +271,105 @@ fn extract_stylesheet_links(html: &str, base: &Url) -> Vec<Url> {
 }
 
 /// Fetch an image URL and return the raw bytes.
-pub(crate) async fn fetch_image(url: String) -> Result<Vec<u8>, String> {
-    let client = &*HTTP_CLIENT;
-    let response = client.get(&url).send().await.map_err(|e| e.to_string())?;
+pub(crate) async fn fetch_image(
+    url: String,
+    options: FetchOptions,
+) -> Result<Vec<u8>, WebViewError> {
+    fetch_bytes(&HTTP_CLIENT, &url, MAX_IMAGE_SIZE, &options).await
+}
 
-    if let Some(len) = response.content_length() {
-        if len > MAX_IMAGE_SIZE {
-            return Err(format!(
-                "image too large: {len} bytes exceeds {MAX_IMAGE_SIZE} byte limit"
-            ));
+/// Run a fetch task from synchronous engine code.
+#[cfg(feature = "blitz")]
+pub(crate) fn spawn(task: impl std::future::Future<Output = ()> + Send + 'static) {
+    RUNTIME.spawn(task);
+}
+
+/// Fetch a single response body through the configured [`FetchMode`],
+/// enforcing `limit` bytes.
+async fn fetch_bytes(
+    client: &reqwest::Client,
+    url: &str,
+    limit: u64,
+    options: &FetchOptions,
+) -> Result<Vec<u8>, WebViewError> {
+    match &options.mode {
+        FetchMode::Network => fetch_with_retry(client, url, limit, &options.retry).await,
+        FetchMode::Record(dir) => {
+            let bytes = fetch_with_retry(client, url, limit, &options.retry).await?;
+            archive::store(dir, url, &bytes)
+                .map_err(|e| WebViewError::Record(format!("{url}: {e}")))?;
+            Ok(bytes)
+        }
+        FetchMode::Replay(dir) => {
+            let bytes = archive::load(dir, url)?;
+            check_size(bytes.len() as u64, limit)?;
+            Ok(bytes)
         }
     }
+}
 
-    let bytes = response.bytes().await.map_err(|e| e.to_string())?;
-    if bytes.len() as u64 > MAX_IMAGE_SIZE {
-        return Err(format!(
-            "image too large: {} bytes exceeds {MAX_IMAGE_SIZE} byte limit",
-            bytes.len()
-        ));
+/// Fetch from the network, retrying transient failures per `retry`.
+async fn fetch_with_retry(
+    client: &reqwest::Client,
+    url: &str,
+    limit: u64,
+    retry: &RetryPolicy,
+) -> Result<Vec<u8>, WebViewError> {
+    let mut attempt = 0;
+    loop {
+        match fetch_network(client, url, limit).await {
+            Err(e) if e.is_transient() && attempt < retry.max_retries => {
+                tokio::time::sleep(retry.backoff(attempt)).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
     }
+}
+
+async fn fetch_network(
+    client: &reqwest::Client,
+    url: &str,
+    limit: u64,
+) -> Result<Vec<u8>, WebViewError> {
+    let response = client.get(url).send().await.map_err(classify)?;
+
+    // Only gateway failures are worth retrying; any other status still
+    // carries a page (404s, 500s) or resource body to render.
+    let status = response.status().as_u16();
+    if matches!(status, 502..=504) {
+        return Err(WebViewError::HttpStatus(status));
+    }
+
+    if let Some(len) = response.content_length() {
+        check_size(len, limit)?;
+    }
+
+    let bytes = response.bytes().await.map_err(classify)?;
+    check_size(bytes.len() as u64, limit)?;
 
     Ok(bytes.to_vec())
 }
 
+fn check_size(size: u64, limit: u64) -> Result<(), WebViewError> {
+    if size > limit {
+        return Err(WebViewError::SizeLimit { size, limit });
+    }
+    Ok(())
+}
+
+fn classify(e: reqwest::Error) -> WebViewError {
+    if e.is_timeout() {
+        WebViewError::Timeout
+    } else if e.is_decode() {
+        WebViewError::Decode(e.to_string())
+    } else if e.is_builder() {
+        WebViewError::InvalidUrl(e.to_string())
+    } else {
+        WebViewError::Network(e.to_string())
+    }
+}
+
 /// Pull the value of a named attribute out of a single HTML tag string.
 fn extract_attr(tag: &str, name: &str) -> Option<String> {
     let lower = tag.to_ascii_lowercase();
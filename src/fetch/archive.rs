@@ -0,0 +1,59 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::WebViewError;
+
+/// Max number of URL characters kept readable in an archive file name.
+const MAX_NAME_PREFIX: usize = 96;
+
+/// Map a URL to its response file inside an archive directory.
+///
+/// The file name is a sanitized, truncated copy of the URL followed by a
+/// stable hash of the full URL, so entries stay readable on disk while long
+/// or similar URLs can't collide. The fragment is ignored — it never
+/// reaches the server, so `page#a` and `page#b` share one response.
+pub(crate) fn entry_path(dir: &Path, url: &str) -> PathBuf {
+    let key = url.split('#').next().unwrap_or(url);
+
+    let mut name: String = key
+        .chars()
+        .take(MAX_NAME_PREFIX)
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    name.push_str(&format!("-{:016x}", fnv1a(key.as_bytes())));
+
+    dir.join(name)
+}
+
+/// Read a recorded response body. A missing entry is reported as an error
+/// so replayed renders never silently fall back to the network.
+pub(crate) fn load(dir: &Path, url: &str) -> Result<Vec<u8>, WebViewError> {
+    let path = entry_path(dir, url);
+    std::fs::read(&path).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => WebViewError::NotInArchive(url.to_string()),
+        _ => WebViewError::NotInArchive(format!("{url} ({}: {e})", path.display())),
+    })
+}
+
+/// Write a response body into the archive, replacing any previous entry.
+pub(crate) fn store(dir: &Path, url: &str, bytes: &[u8]) -> io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    std::fs::write(entry_path(dir, url), bytes)
+}
+
+/// 64-bit FNV-1a. Used instead of `DefaultHasher`, whose output may change
+/// between Rust releases and would invalidate recorded archives.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for b in bytes {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
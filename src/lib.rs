@@ -24,13 +24,26 @@
 //! Then call the usual `view/update` methods — see
 //! [examples](https://github.com/franzos/iced_webview_v2/tree/main/examples) for full working code.
 //!
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
 
+use bytes::Bytes;
 use iced::widget::image;
+use iced::Rectangle;
 
 /// Engine Trait and Engine implementations
 pub mod engines;
-pub use engines::{Engine, PageType, PixelFormat, ViewId};
+pub use engines::host::EngineHost;
+pub use engines::{Capabilities, Engine, PageType, PixelFormat, ViewId};
+
+mod error;
+pub use error::WebViewError;
+
+mod event;
+pub use event::{EngineEvent, ScriptId, ScriptValue, WebViewEvent};
+
+mod waker;
+pub use waker::Waker;
 
 mod webview;
 pub use basic::{Action, WebView};
@@ -48,30 +61,58 @@ pub use engines::servo::Servo;
 #[cfg(feature = "cef")]
 pub use engines::cef_engine::{cef_subprocess_check, Cef};
 
+#[cfg(any(
+    feature = "litehtml",
+    feature = "blitz",
+    feature = "servo",
+    feature = "cef"
+))]
+pub use engines::any::{AnyEngine, EngineKind};
+
 pub(crate) mod util;
 
+#[cfg(feature = "testing")]
+pub mod testing;
+
 #[cfg(any(feature = "litehtml", feature = "blitz"))]
 pub(crate) mod fetch;
+#[cfg(any(feature = "litehtml", feature = "blitz"))]
+pub use fetch::{FetchMode, RetryPolicy};
 
 /// Image details for passing the view around
+///
+/// The pixels live in one reference-counted buffer, in the engine's
+/// [`PixelFormat`], so cloning an `ImageInfo` never copies them. The shader
+/// widget uploads them as they are; the image handle shares the buffer when
+/// it is already straight RGBA and is converted once otherwise. litehtml and
+/// Blitz convert when they draw a tile, on the engine's thread; other frames
+/// are converted on first use.
 #[derive(Clone, Debug)]
 pub struct ImageInfo {
     width: u32,
     height: u32,
-    handle: image::Handle,
-    raw_pixels: Arc<Vec<u8>>,
+    format: PixelFormat,
+    pixels: Bytes,
+    handle: Arc<OnceLock<image::Handle>>,
+    /// Unique per frame; clones share it.
+    frame: u64,
+    damage: Option<Damage>,
 }
 
+/// Regions of a frame that differ from an earlier frame.
+#[derive(Clone, Debug)]
+struct Damage {
+    /// `frame` of the image the regions are relative to.
+    base: u64,
+    /// Changed rectangles in physical pixels.
+    rects: Arc<[Rectangle<u32>]>,
+}
+
+static NEXT_FRAME: AtomicU64 = AtomicU64::new(1);
+
 impl Default for ImageInfo {
     fn default() -> Self {
-        let pixels = vec![255; (Self::WIDTH as usize * Self::HEIGHT as usize) * 4];
-        let raw_pixels = Arc::new(pixels.clone());
-        Self {
-            width: Self::WIDTH,
-            height: Self::HEIGHT,
-            handle: image::Handle::from_rgba(Self::WIDTH, Self::HEIGHT, pixels),
-            raw_pixels,
-        }
+        Self::blank(Self::WIDTH, Self::HEIGHT)
     }
 }
 
@@ -80,26 +121,98 @@ impl ImageInfo {
     const WIDTH: u32 = 800;
     const HEIGHT: u32 = 800;
 
-    fn new(mut pixels: Vec<u8>, format: PixelFormat, width: u32, height: u32) -> Self {
-        // R, G, B, A
+    fn new(pixels: Vec<u8>, format: PixelFormat, width: u32, height: u32) -> Self {
+        // Four bytes per pixel in every format
         assert_eq!(pixels.len() % 4, 0);
 
-        if let PixelFormat::Bgra = format {
-            pixels.chunks_mut(4).for_each(|chunk| chunk.swap(0, 2));
+        // Takes ownership of the allocation without copying.
+        let pixels = Bytes::from(pixels);
+        let handle = OnceLock::new();
+        if format == PixelFormat::Rgba {
+            let _ = handle.set(image::Handle::from_rgba(width, height, pixels.clone()));
         }
-
-        let raw_pixels = Arc::new(pixels.clone());
         Self {
             width,
             height,
-            handle: image::Handle::from_rgba(width, height, pixels),
-            raw_pixels,
+            format,
+            pixels,
+            handle: Arc::new(handle),
+            frame: NEXT_FRAME.fetch_add(1, Ordering::Relaxed),
+            damage: None,
         }
     }
 
+    /// Mark this frame as differing from `base` only inside `rects`, so the
+    /// shader widget can upload just those regions over `base`. Ignored
+    /// when the sizes differ.
+    #[cfg_attr(
+        not(any(feature = "litehtml", feature = "blitz", feature = "cef")),
+        allow(dead_code)
+    )]
+    fn with_damage(mut self, base: &ImageInfo, rects: Vec<Rectangle<u32>>) -> Self {
+        if (base.width, base.height) == (self.width, self.height) {
+            self.damage = Some(Damage {
+                base: base.frame,
+                rects: rects.into(),
+            });
+        }
+        self
+    }
+
+    /// Identifies the frame; clones share it.
+    #[cfg_attr(
+        not(any(
+            feature = "litehtml",
+            feature = "blitz",
+            feature = "servo",
+            feature = "cef"
+        )),
+        allow(dead_code)
+    )]
+    fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// The regions that changed since frame `base`, or `None` when this
+    /// frame has to be taken whole.
+    #[cfg_attr(
+        not(any(
+            feature = "litehtml",
+            feature = "blitz",
+            feature = "servo",
+            feature = "cef"
+        )),
+        allow(dead_code)
+    )]
+    fn damage_since(&self, base: u64) -> Option<&[Rectangle<u32>]> {
+        self.damage
+            .as_ref()
+            .filter(|damage| damage.base == base)
+            .map(|damage| &*damage.rects)
+    }
+
+    /// The pixel buffer, for an engine to take back and paint into once no
+    /// other clone holds it.
+    #[cfg_attr(not(feature = "cef"), allow(dead_code))]
+    fn into_pixels(self) -> Bytes {
+        self.pixels
+    }
+
+    /// Build the image handle now rather than on first use, so the CPU
+    /// conversion runs on the thread producing the frame.
+    #[cfg_attr(not(any(feature = "litehtml", feature = "blitz")), allow(dead_code))]
+    fn with_handle(self) -> Self {
+        let _ = self.as_handle();
+        self
+    }
+
     /// Get the image handle for direct rendering.
     pub fn as_handle(&self) -> image::Handle {
-        self.handle.clone()
+        self.handle
+            .get_or_init(|| {
+                image::Handle::from_rgba(self.width, self.height, self.convert_to_rgba())
+            })
+            .clone()
     }
 
     /// Image width.
@@ -112,19 +225,101 @@ impl ImageInfo {
         self.height
     }
 
-    /// Raw RGBA pixel data for direct GPU upload (shader widget path).
-    pub fn pixels(&self) -> Arc<Vec<u8>> {
-        Arc::clone(&self.raw_pixels)
+    /// Layout of the bytes returned by [`raw_pixels`](Self::raw_pixels).
+    pub fn format(&self) -> PixelFormat {
+        self.format
+    }
+
+    /// The pixels as straight-alpha RGBA. They are shared with the image
+    /// handle, so a frame in another [`format`](Self::format) is converted
+    /// on the CPU once, on first use.
+    pub fn pixels(&self) -> Bytes {
+        match self.as_handle() {
+            image::Handle::Rgba { pixels, .. } => pixels,
+            _ => unreachable!("the image handle is built from RGBA pixels"),
+        }
+    }
+
+    /// Raw pixel data in [`format`](Self::format), for direct GPU upload
+    /// (shader widget path). Cloning it is cheap.
+    pub fn raw_pixels(&self) -> Bytes {
+        self.pixels.clone()
+    }
+
+    /// The pixels converted to straight-alpha RGBA unless they already are.
+    fn convert_to_rgba(&self) -> Bytes {
+        if self.format == PixelFormat::Rgba {
+            return self.pixels.clone();
+        }
+        let swap = self.format.is_bgra();
+        let premultiplied = self.format.is_premultiplied();
+        let mut rgba = Vec::with_capacity(self.pixels.len());
+        for chunk in self.pixels.chunks_exact(4) {
+            let (mut r, g, mut b, a) = (chunk[0], chunk[1], chunk[2], chunk[3]);
+            if swap {
+                std::mem::swap(&mut r, &mut b);
+            }
+            if premultiplied {
+                rgba.extend_from_slice(&[
+                    unpremultiply(r, a),
+                    unpremultiply(g, a),
+                    unpremultiply(b, a),
+                    a,
+                ]);
+            } else {
+                rgba.extend_from_slice(&[r, g, b, a]);
+            }
+        }
+        Bytes::from(rgba)
     }
 
     fn blank(width: u32, height: u32) -> Self {
-        let pixels = vec![255; (width as usize * height as usize) * 4];
-        let raw_pixels = Arc::new(pixels.clone());
-        Self {
+        Self::new(
+            vec![255; (width as usize * height as usize) * 4],
+            PixelFormat::Rgba,
             width,
             height,
-            handle: image::Handle::from_rgba(width, height, pixels),
-            raw_pixels,
-        }
+        )
+    }
+}
+
+fn unpremultiply(channel: u8, alpha: u8) -> u8 {
+    let a = alpha as u32;
+    if a == 0 {
+        return 0;
+    }
+    ((channel as u32 * 255 + a / 2) / a).min(255) as u8
+}
+
+/// A horizontal band of a document rasterized by a tiling engine.
+///
+/// Blitz and litehtml rasterize only the tiles around the scroll position;
+/// the widget draws each one at its document offset.
+#[derive(Clone, Debug)]
+pub struct Tile {
+    top: f32,
+    height: f32,
+    image: ImageInfo,
+}
+
+impl Tile {
+    #[cfg_attr(not(any(feature = "litehtml", feature = "blitz")), allow(dead_code))]
+    fn new(top: f32, height: f32, image: ImageInfo) -> Self {
+        Self { top, height, image }
+    }
+
+    /// Offset of the tile's top edge from the top of the document, in logical pixels.
+    pub fn top(&self) -> f32 {
+        self.top
+    }
+
+    /// Height of the tile in logical pixels.
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+
+    /// The tile's pixels, at physical size.
+    pub fn image(&self) -> &ImageInfo {
+        &self.image
     }
 }
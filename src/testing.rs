@@ -0,0 +1,567 @@
+//! Test support for apps built on the widgets.
+//!
+//! [`MockEngine`] implements [`Engine`] without rendering anything: it records
+//! every call and lets a test script what the "page" does: URL and title
+//! changes, anchor clicks, discovered images, frames. [`update`] and
+//! [`update_advanced`] run a widget update and return the messages it emitted.
+//!
+//! ```rust,ignore
+//! use iced_webview::testing::{self, MockCall, MockEngine};
+//! use iced_webview::{basic::Action, PageType, WebView};
+//!
+//! let mut webview = WebView::<MockEngine, Message>::new().on_url_change(Message::UrlChanged);
+//! testing::update(&mut webview, Action::CreateView(PageType::Html("<p>hi</p>".into())));
+//! testing::update(&mut webview, Action::ChangeView(0));
+//!
+//! let id = webview.engine().view_ids()[0];
+//! webview.engine_mut().click_anchor(id, "https://example.com/");
+//! testing::update(&mut webview, Action::SendMouseEvent(click, point));
+//! assert!(webview.engine().calls().contains(&MockCall::Goto(
+//!     id,
+//!     PageType::Url("https://example.com/".into())
+//! )));
+//! ```
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use iced::futures::{executor, StreamExt};
+use iced::keyboard;
+use iced::mouse::{self, Interaction};
+use iced::{Point, Size, Task};
+
+use crate::{
+    advanced, basic, Capabilities, Engine, EngineEvent, ImageInfo, PageType, PixelFormat, ScriptId,
+    ViewId, WebViewError, WebViewEvent,
+};
+
+/// A call made on a [`MockEngine`], in the order it happened.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum MockCall {
+    /// `update()`
+    Update,
+    /// `render(size)`
+    Render(Size<u32>),
+    /// `request_render(id, size)`
+    RequestRender(ViewId),
+    /// `new_view(size, content)`, with the id it returned
+    NewView(ViewId, Option<PageType>),
+    /// `remove_view(id)`
+    RemoveView(ViewId),
+    /// `focus()`
+    Focus,
+    /// `unfocus()`
+    Unfocus,
+    /// `resize(size)`
+    Resize(Size<u32>),
+    /// `set_scale_factor(scale)`
+    SetScaleFactor(f32),
+    /// `set_fetch_policy(mode, retry)`
+    #[cfg(any(feature = "litehtml", feature = "blitz"))]
+    SetFetchPolicy(crate::FetchMode, crate::RetryPolicy),
+    /// `handle_keyboard_event(id, event)`
+    KeyboardEvent(ViewId),
+    /// `handle_mouse_event(id, point, event)`
+    MouseEvent(ViewId, Point),
+    /// `scroll(id, delta)`
+    Scroll(ViewId),
+    /// `goto(id, page_type)`
+    Goto(ViewId, PageType),
+    /// `refresh(id)`
+    Refresh(ViewId),
+    /// `go_forward(id)`
+    GoForward(ViewId),
+    /// `go_back(id)`
+    GoBack(ViewId),
+    /// `evaluate_script(id, script_id, script)`
+    EvaluateScript(ViewId, ScriptId, String),
+    /// `scroll_to_fragment(id, fragment)`
+    ScrollToFragment(ViewId, String),
+    /// `set_css_cache(id, cache)`
+    SetCssCache(ViewId),
+    /// `load_image_from_bytes(id, url, ..)`
+    LoadImage(ViewId, String),
+    /// `image_fetch_failed(id, url)`
+    ImageFetchFailed(ViewId, String),
+    /// `flush_staged_images(id, size)`
+    FlushStagedImages(ViewId),
+}
+
+#[derive(Default)]
+struct MockView {
+    url: String,
+    title: String,
+    html: String,
+    cursor: Interaction,
+    frame: ImageInfo,
+    size: Size<u32>,
+    scroll_y: f32,
+    content_height: f32,
+    selected_text: Option<String>,
+    anchor_click: Option<String>,
+    can_go_back: bool,
+    can_go_forward: bool,
+    loading: bool,
+    fragments: Vec<String>,
+}
+
+/// An [`Engine`] that renders nothing and records every call.
+///
+/// Views get sequential ids starting at 1. Like the real engines, navigating
+/// to a URL queues a [`WebViewEvent::UrlChanged`]; everything else happens
+/// only when the test scripts it.
+pub struct MockEngine {
+    views: Vec<(ViewId, MockView)>,
+    next_id: ViewId,
+    handles_urls: bool,
+    capabilities: Option<Capabilities>,
+    rendering: bool,
+    pending_images: Vec<(ViewId, String, String, bool)>,
+    events: Vec<EngineEvent>,
+    calls: RefCell<Vec<MockCall>>,
+}
+
+impl Default for MockEngine {
+    fn default() -> Self {
+        Self {
+            views: Vec::new(),
+            next_id: 1,
+            handles_urls: true,
+            capabilities: None,
+            rendering: false,
+            pending_images: Vec::new(),
+            events: Vec::new(),
+            calls: RefCell::new(Vec::new()),
+        }
+    }
+}
+
+impl MockEngine {
+    /// Whether the engine claims to fetch URLs itself. With `false` the
+    /// widgets fetch pages and images through the crate's fetch layer, so
+    /// use [`FetchMode::Replay`](crate::FetchMode::Replay) to stay offline.
+    pub fn with_handles_urls(mut self, handles_urls: bool) -> Self {
+        self.handles_urls = handles_urls;
+        self
+    }
+
+    /// Report these capabilities instead of the default ones.
+    pub fn with_capabilities(mut self, capabilities: Capabilities) -> Self {
+        self.capabilities = Some(capabilities);
+        self
+    }
+
+    /// Draw a blank frame of the view's size on every render, like an
+    /// engine that renders on demand. Off by default, so frames only change
+    /// through [`set_frame`](Self::set_frame).
+    pub fn with_rendering(mut self, rendering: bool) -> Self {
+        self.rendering = rendering;
+        self
+    }
+
+    /// Every call made so far, oldest first.
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.calls.borrow().clone()
+    }
+
+    /// Return the calls made so far and forget them.
+    pub fn take_calls(&mut self) -> Vec<MockCall> {
+        self.calls.take()
+    }
+
+    /// The last HTML passed to `new_view` or `goto` for a view.
+    pub fn html(&self, id: ViewId) -> Option<&str> {
+        self.find_view(id).map(|v| v.html.as_str())
+    }
+
+    /// Queue an arbitrary event for the view.
+    pub fn push_event(&mut self, id: ViewId, event: WebViewEvent) {
+        self.events.push(EngineEvent::new(id, event));
+    }
+
+    /// Navigate the view as if the page did it, e.g. a redirect.
+    pub fn set_url(&mut self, id: ViewId, url: impl Into<String>) {
+        let url = url.into();
+        if let Some(view) = self.find_view_mut(id) {
+            view.url = url.clone();
+            self.push_event(id, WebViewEvent::UrlChanged(url));
+        }
+    }
+
+    /// Change the document title.
+    pub fn set_title(&mut self, id: ViewId, title: impl Into<String>) {
+        let title = title.into();
+        if let Some(view) = self.find_view_mut(id) {
+            view.title = title.clone();
+            self.push_event(id, WebViewEvent::TitleChanged(title));
+        }
+    }
+
+    /// Change the cursor the page requests.
+    pub fn set_cursor(&mut self, id: ViewId, cursor: Interaction) {
+        if let Some(view) = self.find_view_mut(id) {
+            view.cursor = cursor;
+            self.push_event(id, WebViewEvent::CursorChanged(cursor));
+        }
+    }
+
+    /// Make the next mouse event on the view click a link to `href`.
+    pub fn click_anchor(&mut self, id: ViewId, href: impl Into<String>) {
+        if let Some(view) = self.find_view_mut(id) {
+            view.anchor_click = Some(href.into());
+        }
+    }
+
+    /// Report an image discovered during layout, as returned by
+    /// `take_pending_images`.
+    pub fn add_pending_image(
+        &mut self,
+        id: ViewId,
+        src: impl Into<String>,
+        baseurl: impl Into<String>,
+        redraw_on_ready: bool,
+    ) {
+        self.pending_images
+            .push((id, src.into(), baseurl.into(), redraw_on_ready));
+    }
+
+    /// Show an RGBA frame with the given size.
+    pub fn set_frame(&mut self, id: ViewId, rgba: Vec<u8>, width: u32, height: u32) {
+        if let Some(view) = self.find_view_mut(id) {
+            view.frame = ImageInfo::new(rgba, PixelFormat::Rgba, width, height);
+        }
+    }
+
+    /// Scroll state reported to the widget. A non-zero content height makes
+    /// the widgets treat the view as one they scroll themselves.
+    pub fn set_scroll(&mut self, id: ViewId, scroll_y: f32, content_height: f32) {
+        if let Some(view) = self.find_view_mut(id) {
+            view.scroll_y = scroll_y;
+            view.content_height = content_height;
+        }
+    }
+
+    /// Text returned by `get_selected_text`.
+    pub fn set_selected_text(&mut self, id: ViewId, text: Option<String>) {
+        if let Some(view) = self.find_view_mut(id) {
+            view.selected_text = text;
+        }
+    }
+
+    /// Navigation state returned by `can_go_back` / `can_go_forward`.
+    pub fn set_history(&mut self, id: ViewId, can_go_back: bool, can_go_forward: bool) {
+        if let Some(view) = self.find_view_mut(id) {
+            view.can_go_back = can_go_back;
+            view.can_go_forward = can_go_forward;
+        }
+    }
+
+    /// Loading state returned by `is_loading`.
+    pub fn set_loading(&mut self, id: ViewId, loading: bool) {
+        if let Some(view) = self.find_view_mut(id) {
+            view.loading = loading;
+        }
+    }
+
+    /// Fragments `scroll_to_fragment` finds; any other fragment is missing.
+    pub fn set_fragments(&mut self, id: ViewId, fragments: &[&str]) {
+        if let Some(view) = self.find_view_mut(id) {
+            view.fragments = fragments.iter().map(|f| f.to_string()).collect();
+        }
+    }
+
+    fn record(&self, call: MockCall) {
+        self.calls.borrow_mut().push(call);
+    }
+
+    fn find_view(&self, id: ViewId) -> Option<&MockView> {
+        self.views.iter().find(|(v, _)| *v == id).map(|(_, v)| v)
+    }
+
+    fn find_view_mut(&mut self, id: ViewId) -> Option<&mut MockView> {
+        self.views
+            .iter_mut()
+            .find(|(v, _)| *v == id)
+            .map(|(_, v)| v)
+    }
+
+    /// Draw the view's blank frame when rendering is on.
+    fn draw(&mut self, id: ViewId) {
+        if !self.rendering {
+            return;
+        }
+        if let Some(view) = self.find_view_mut(id) {
+            view.frame = ImageInfo::blank(view.size.width, view.size.height);
+        }
+    }
+}
+
+impl Engine for MockEngine {
+    fn update(&mut self) {
+        self.record(MockCall::Update);
+    }
+
+    fn render(&mut self, size: Size<u32>) {
+        self.record(MockCall::Render(size));
+        let ids: Vec<ViewId> = self.views.iter().map(|(id, _)| *id).collect();
+        for id in ids {
+            self.draw(id);
+        }
+    }
+
+    fn request_render(&mut self, id: ViewId, _size: Size<u32>) {
+        self.record(MockCall::RequestRender(id));
+        self.draw(id);
+    }
+
+    fn new_view(&mut self, size: Size<u32>, content: Option<PageType>) -> ViewId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.record(MockCall::NewView(id, content.clone()));
+
+        let mut view = MockView {
+            size,
+            ..MockView::default()
+        };
+        match content {
+            Some(PageType::Url(url)) => {
+                view.url = url.clone();
+                self.events
+                    .push(EngineEvent::new(id, WebViewEvent::UrlChanged(url)));
+            }
+            Some(PageType::Html(html)) => view.html = html,
+            None => {}
+        }
+        self.views.push((id, view));
+        id
+    }
+
+    fn remove_view(&mut self, id: ViewId) {
+        self.record(MockCall::RemoveView(id));
+        self.views.retain(|(v, _)| *v != id);
+    }
+
+    fn has_view(&self, id: ViewId) -> bool {
+        self.find_view(id).is_some()
+    }
+
+    fn focus(&mut self) {
+        self.record(MockCall::Focus);
+    }
+
+    fn unfocus(&self) {
+        self.record(MockCall::Unfocus);
+    }
+
+    fn resize(&mut self, size: Size<u32>) {
+        self.record(MockCall::Resize(size));
+        for (_, view) in &mut self.views {
+            view.size = size;
+        }
+    }
+
+    fn set_scale_factor(&mut self, scale: f32) {
+        self.record(MockCall::SetScaleFactor(scale));
+    }
+
+    #[cfg(any(feature = "litehtml", feature = "blitz"))]
+    fn set_fetch_policy(&mut self, mode: crate::FetchMode, retry: crate::RetryPolicy) {
+        self.record(MockCall::SetFetchPolicy(mode, retry));
+    }
+
+    fn handles_urls(&self) -> bool {
+        self.handles_urls
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.capabilities.unwrap_or(Capabilities {
+            native_urls: self.handles_urls,
+            ..Capabilities::default()
+        })
+    }
+
+    fn handle_keyboard_event(&mut self, id: ViewId, _event: keyboard::Event) {
+        self.record(MockCall::KeyboardEvent(id));
+    }
+
+    fn handle_mouse_event(&mut self, id: ViewId, point: Point, _event: mouse::Event) {
+        self.record(MockCall::MouseEvent(id, point));
+    }
+
+    fn scroll(&mut self, id: ViewId, _delta: mouse::ScrollDelta) {
+        self.record(MockCall::Scroll(id));
+    }
+
+    fn goto(&mut self, id: ViewId, page_type: PageType) {
+        self.record(MockCall::Goto(id, page_type.clone()));
+        match page_type {
+            PageType::Url(url) => self.set_url(id, url),
+            PageType::Html(html) => {
+                if let Some(view) = self.find_view_mut(id) {
+                    view.html = html;
+                }
+            }
+        }
+    }
+
+    fn refresh(&mut self, id: ViewId) {
+        self.record(MockCall::Refresh(id));
+    }
+
+    fn go_forward(&mut self, id: ViewId) {
+        self.record(MockCall::GoForward(id));
+    }
+
+    fn go_back(&mut self, id: ViewId) {
+        self.record(MockCall::GoBack(id));
+    }
+
+    /// Accepted when the capabilities include `javascript`; the test then
+    /// answers with a [`WebViewEvent::ScriptResult`] via `push_event`.
+    fn evaluate_script(
+        &mut self,
+        id: ViewId,
+        script_id: ScriptId,
+        script: String,
+    ) -> Result<(), WebViewError> {
+        self.record(MockCall::EvaluateScript(id, script_id, script));
+        if self.capabilities().javascript {
+            Ok(())
+        } else {
+            Err(WebViewError::Unsupported("JavaScript evaluation".into()))
+        }
+    }
+
+    fn can_go_back(&self, id: ViewId) -> bool {
+        self.find_view(id).is_some_and(|v| v.can_go_back)
+    }
+
+    fn can_go_forward(&self, id: ViewId) -> bool {
+        self.find_view(id).is_some_and(|v| v.can_go_forward)
+    }
+
+    fn is_loading(&self, id: ViewId) -> bool {
+        self.find_view(id).is_some_and(|v| v.loading)
+    }
+
+    fn get_url(&self, id: ViewId) -> String {
+        match self.find_view(id) {
+            Some(view) if !view.url.is_empty() => view.url.clone(),
+            _ => "about:blank".to_string(),
+        }
+    }
+
+    fn get_title(&self, id: ViewId) -> String {
+        self.find_view(id)
+            .map(|v| v.title.clone())
+            .unwrap_or_default()
+    }
+
+    fn get_cursor(&self, id: ViewId) -> Interaction {
+        self.find_view(id)
+            .map(|v| v.cursor)
+            .unwrap_or(Interaction::Idle)
+    }
+
+    fn get_view(&self, id: ViewId) -> &ImageInfo {
+        static BLANK: std::sync::LazyLock<ImageInfo> = std::sync::LazyLock::new(ImageInfo::default);
+        self.find_view(id).map(|v| &v.frame).unwrap_or(&BLANK)
+    }
+
+    fn get_scroll_y(&self, id: ViewId) -> f32 {
+        self.find_view(id).map(|v| v.scroll_y).unwrap_or(0.0)
+    }
+
+    fn get_content_height(&self, id: ViewId) -> f32 {
+        self.find_view(id).map(|v| v.content_height).unwrap_or(0.0)
+    }
+
+    fn get_selected_text(&self, id: ViewId) -> Option<String> {
+        self.find_view(id)?.selected_text.clone()
+    }
+
+    fn take_anchor_click(&mut self, id: ViewId) -> Option<String> {
+        self.find_view_mut(id)?.anchor_click.take()
+    }
+
+    fn scroll_to_fragment(&mut self, id: ViewId, fragment: &str) -> bool {
+        self.record(MockCall::ScrollToFragment(id, fragment.to_string()));
+        self.find_view(id)
+            .is_some_and(|v| v.fragments.iter().any(|f| f == fragment))
+    }
+
+    fn take_pending_images(&mut self) -> Vec<(ViewId, String, String, bool)> {
+        std::mem::take(&mut self.pending_images)
+    }
+
+    fn take_events(&mut self) -> Vec<EngineEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    fn set_css_cache(&mut self, id: ViewId, _cache: HashMap<String, String>) {
+        self.record(MockCall::SetCssCache(id));
+    }
+
+    fn load_image_from_bytes(
+        &mut self,
+        id: ViewId,
+        url: &str,
+        _bytes: &[u8],
+        _redraw_on_ready: bool,
+    ) {
+        self.record(MockCall::LoadImage(id, url.to_string()));
+    }
+
+    fn image_fetch_failed(&mut self, id: ViewId, url: &str) {
+        self.record(MockCall::ImageFetchFailed(id, url.to_string()));
+    }
+
+    fn flush_staged_images(&mut self, id: ViewId, _size: Size<u32>) {
+        self.record(MockCall::FlushStagedImages(id));
+    }
+
+    fn view_ids(&self) -> Vec<ViewId> {
+        self.views.iter().map(|(id, _)| *id).collect()
+    }
+}
+
+/// Run a task to completion on the current thread and return its messages.
+///
+/// Page and image fetches run too, so tests that exercise them should use
+/// [`FetchMode::Replay`](crate::FetchMode::Replay) to stay offline.
+pub fn collect<T>(task: Task<T>) -> Vec<T> {
+    let Some(stream) = iced_runtime::task::into_stream(task) else {
+        return Vec::new();
+    };
+    executor::block_on(stream.collect::<Vec<_>>())
+        .into_iter()
+        .filter_map(|action| match action {
+            iced_runtime::Action::Output(message) => Some(message),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Run `basic::WebView::update` and return the messages it emitted.
+pub fn update<E, Message>(
+    webview: &mut basic::WebView<E, Message>,
+    action: basic::Action,
+) -> Vec<Message>
+where
+    E: Engine + Default,
+    Message: Send + Clone + 'static,
+{
+    collect(webview.update(action))
+}
+
+/// Run `advanced::WebView::update` and return the messages it emitted.
+pub fn update_advanced<E, Message>(
+    webview: &mut advanced::WebView<E, Message>,
+    action: advanced::Action,
+) -> Vec<Message>
+where
+    E: Engine + Default,
+    Message: Send + Clone + 'static,
+{
+    collect(webview.update(action))
+}
@@ -8,6 +8,15 @@ pub(crate) fn html_escape(s: &str) -> String {
         .replace('"', "&quot;")
 }
 
+/// The page shown when a fetch fails and the app has no `error_page` hook.
+pub(crate) fn default_error_page(url: &str, error: &crate::WebViewError) -> String {
+    format!(
+        "<html><body><h1>Failed to load</h1><p>{}</p><p>{}</p></body></html>",
+        html_escape(url),
+        html_escape(&error.to_string()),
+    )
+}
+
 /// Resolve a resource URL (image, CSS) with a 3-tier fallback:
 /// 1. Parse `src` as absolute URL
 /// 2. Resolve against `baseurl` (e.g. stylesheet URL)
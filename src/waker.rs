@@ -0,0 +1,78 @@
+use std::hash::{Hash, Hasher};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use iced::futures::task::AtomicWaker;
+use iced::futures::Stream;
+
+#[derive(Default)]
+struct Shared {
+    woken: AtomicBool,
+    task: AtomicWaker,
+}
+
+/// Wakes an idle widget subscription so the next `Update` runs.
+///
+/// The widgets hand one to their engine through [`Engine::set_waker`]. Engines
+/// whose work arrives from other threads (Servo's event loop, CEF's message
+/// pump) call [`Waker::wake`] instead of relying on a polling timer.
+///
+/// [`Engine::set_waker`]: crate::Engine::set_waker
+#[derive(Clone, Default)]
+pub struct Waker(Arc<Shared>);
+
+impl Waker {
+    /// Request an `Update`. Safe to call from any thread; wakeups that arrive
+    /// before the subscription polls again are coalesced into one.
+    pub fn wake(&self) {
+        self.0.woken.store(true, Ordering::Release);
+        self.0.task.wake();
+    }
+
+    /// Whether [`wake`](Self::wake) was called since the last check, and
+    /// clear it. Takes the wakeup from the subscription, so only for tests.
+    #[cfg(feature = "testing")]
+    pub fn take_wakeup(&self) -> bool {
+        self.0.woken.swap(false, Ordering::AcqRel)
+    }
+
+    /// A stream that yields once per (coalesced) wakeup.
+    pub(crate) fn wakeups(&self) -> Wakeups {
+        Wakeups(self.clone())
+    }
+}
+
+impl std::fmt::Debug for Waker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Waker")
+            .field("woken", &self.0.woken.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+// Identity is the shared state, so a widget keeps one subscription alive
+// across `subscription()` calls.
+impl Hash for Waker {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.0).hash(state);
+    }
+}
+
+pub(crate) struct Wakeups(Waker);
+
+impl Stream for Wakeups {
+    type Item = ();
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<()>> {
+        let shared = &(self.0).0;
+        // Register before checking so a wake between the two isn't lost.
+        shared.task.register(cx.waker());
+        if shared.woken.swap(false, Ordering::AcqRel) {
+            Poll::Ready(Some(()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
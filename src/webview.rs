@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 /// Advanced is a more complex interface than basic and assumes the user stores all the view ids themselves.
 /// This gives the user more freedom by allowing them to view multiple views at the same time, but removes
 /// actions like close current
@@ -7,6 +9,18 @@ pub mod advanced;
 pub mod basic;
 
 /// Shader-based rendering widget for engines that manage their own scrolling
-/// (e.g. servo, cef). Uses direct GPU texture updates to avoid Handle cache churn.
-#[cfg(any(feature = "servo", feature = "cef"))]
+/// (e.g. servo, cef), and for litehtml and Blitz with `shader_rendering`.
+/// Uses direct GPU texture updates to avoid Handle cache churn.
+#[cfg(any(
+    feature = "litehtml",
+    feature = "blitz",
+    feature = "servo",
+    feature = "cef"
+))]
 pub(crate) mod shader_widget;
+
+/// How often the widget subscriptions tick while the engine has work.
+pub(crate) const UPDATE_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Fill colour of the text selection overlay.
+pub(crate) const SELECTION_HIGHLIGHT: iced::Color = iced::Color::from_rgba(0.26, 0.52, 0.96, 0.3);
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use iced::advanced::image as core_image;
@@ -10,11 +10,14 @@ use iced::advanced::{
 };
 use iced::keyboard;
 use iced::mouse::{self, Interaction};
-use iced::{Element, Point, Size, Task};
+use iced::{time, Element, Point, Size, Subscription, Task};
 use iced::{Event, Length, Rectangle};
 use url::Url;
 
-use crate::{engines, ImageInfo, PageType, ViewId};
+use crate::{
+    engines, EngineEvent, ImageInfo, PageType, ScriptId, ScriptValue, Tile, ViewId, Waker,
+    WebViewError, WebViewEvent,
+};
 
 #[allow(missing_docs)]
 #[derive(Debug, Clone, PartialEq)]
@@ -39,20 +42,29 @@ pub enum Action {
     Resize(Size<u32>),
     /// Copy the current text selection to clipboard
     CopySelection,
+    /// Run JavaScript in the current view; the result arrives through
+    /// `on_script_result` with the same id
+    EvaluateScript(ScriptId, String),
     /// Internal: carries the result of a URL fetch for engines without native URL support.
     /// On success returns `(html, css_cache)`.
     FetchComplete(
         ViewId,
         String,
-        Result<(String, HashMap<String, String>), String>,
+        Result<(String, HashMap<String, String>), WebViewError>,
     ),
     /// Internal: carries the result of an image fetch.
     /// The bool is `redraw_on_ready` — when true, the image doesn't affect
     /// layout so `doc.render()` can be skipped (redraw only).
     /// The u64 is the navigation epoch — stale results are discarded.
-    ImageFetchComplete(ViewId, String, Result<Vec<u8>, String>, bool, u64),
+    ImageFetchComplete(ViewId, String, Result<Vec<u8>, WebViewError>, bool, u64),
 }
 
+/// Renders the HTML shown when a URL fails to load.
+type ErrorPageFn = dyn Fn(ViewId, &Url, &WebViewError) -> String;
+
+/// Maps a script result to a message.
+type ScriptResultFn<Message> = dyn Fn(ScriptId, Result<ScriptValue, WebViewError>) -> Message;
+
 /// The Basic WebView widget that creates and shows webview(s)
 pub struct WebView<Engine, Message>
 where
@@ -66,10 +78,12 @@ where
     on_close_view: Option<Message>,
     on_create_view: Option<Message>,
     on_url_change: Option<Box<dyn Fn(String) -> Message>>,
-    url: String,
     on_title_change: Option<Box<dyn Fn(String) -> Message>>,
-    title: String,
+    on_event: Option<Box<dyn Fn(WebViewEvent) -> Message>>,
     on_copy: Option<Box<dyn Fn(String) -> Message>>,
+    on_error: Option<Box<dyn Fn(WebViewError) -> Message>>,
+    on_script_result: Option<Box<ScriptResultFn<Message>>>,
+    error_page: Option<Box<ErrorPageFn>>,
     action_mapper: Option<Arc<dyn Fn(Action) -> Message + Send + Sync>>,
     /// Number of image fetches currently in flight. Staged images are only
     /// flushed (triggering an expensive redraw) once this reaches zero, so
@@ -78,6 +92,20 @@ where
     /// Per-view navigation epoch. Incremented on `GoToUrl` so that image
     /// fetches spawned for a previous page are discarded when they complete.
     nav_epochs: HashMap<ViewId, u64>,
+    /// Views showing the page for a failed fetch, where a link to the
+    /// failed URL retries it.
+    error_pages: HashSet<ViewId>,
+    /// Fetch mode and retry policy for engines without native URL support.
+    #[cfg(any(feature = "litehtml", feature = "blitz"))]
+    fetch: crate::fetch::FetchOptions,
+    /// Draw litehtml and Blitz documents with the shader widget.
+    #[cfg(any(feature = "litehtml", feature = "blitz"))]
+    shader_rendering: bool,
+    /// Handed to the engine so it can wake an idle `subscription()`.
+    waker: Waker,
+    /// An action changed state since the last update, so one more update
+    /// is owed even if the engine reports no work of its own.
+    dirty: bool,
 }
 
 impl<Engine: engines::Engine + Default, Message: Send + Clone + 'static> WebView<Engine, Message> {
@@ -90,6 +118,16 @@ impl<Engine: engines::Engine + Default, Message: Send + Clone + 'static> WebView
             .expect("Could find view index for current view. Maybe its already been closed?")
     }
 
+    fn current_view(&self) -> Option<ViewId> {
+        self.current_view_index
+            .and_then(|index| self.view_ids.get(index))
+            .copied()
+    }
+
+    fn is_current_view(&self, id: ViewId) -> bool {
+        self.current_view() == Some(id)
+    }
+
     fn index_as_view_id(&self, index: u32) -> usize {
         *self
             .view_ids
@@ -102,8 +140,23 @@ impl<Engine: engines::Engine + Default, Message: Send + Clone + 'static> Default
     for WebView<Engine, Message>
 {
     fn default() -> Self {
+        Self::with_engine(Engine::default())
+    }
+}
+
+impl<Engine: engines::Engine + Default, Message: Send + Clone + 'static> WebView<Engine, Message> {
+    /// Create new basic WebView widget
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a WebView around an engine you configured yourself,
+    /// e.g. an `AnyEngine` with a custom router.
+    pub fn with_engine(mut engine: Engine) -> Self {
+        let waker = Waker::default();
+        engine.set_waker(waker.clone());
         WebView {
-            engine: Engine::default(),
+            engine,
             view_size: Size {
                 width: 1920,
                 height: 1080,
@@ -114,21 +167,33 @@ impl<Engine: engines::Engine + Default, Message: Send + Clone + 'static> Default
             on_close_view: None,
             on_create_view: None,
             on_url_change: None,
-            url: String::new(),
             on_title_change: None,
-            title: String::new(),
+            on_event: None,
             on_copy: None,
+            on_error: None,
+            on_script_result: None,
+            error_page: None,
             action_mapper: None,
             inflight_images: 0,
             nav_epochs: HashMap::new(),
+            error_pages: HashSet::new(),
+            #[cfg(any(feature = "litehtml", feature = "blitz"))]
+            fetch: crate::fetch::FetchOptions::default(),
+            #[cfg(any(feature = "litehtml", feature = "blitz"))]
+            shader_rendering: false,
+            waker,
+            dirty: false,
         }
     }
-}
 
-impl<Engine: engines::Engine + Default, Message: Send + Clone + 'static> WebView<Engine, Message> {
-    /// Create new basic WebView widget
-    pub fn new() -> Self {
-        Self::default()
+    /// The engine behind the widget, e.g. to ask `AnyEngine` which engine a view runs in.
+    pub fn engine(&self) -> &Engine {
+        &self.engine
+    }
+
+    /// Mutable access to the engine, e.g. to script a `MockEngine` in tests.
+    pub fn engine_mut(&mut self) -> &mut Engine {
+        &mut self.engine
     }
 
     /// Set the display scale factor for HiDPI rendering.
@@ -165,12 +230,50 @@ impl<Engine: engines::Engine + Default, Message: Send + Clone + 'static> WebView
         self
     }
 
+    /// Subscribe to every state change of the current view: navigation,
+    /// title, cursor, content size, selection, favicon and errors.
+    pub fn on_event(mut self, on_event: impl Fn(WebViewEvent) -> Message + 'static) -> Self {
+        self.on_event = Some(Box::new(on_event));
+        self
+    }
+
     /// Subscribe to copy events (text selection copied via Ctrl+C / Cmd+C)
     pub fn on_copy(mut self, on_copy: impl Fn(String) -> Message + 'static) -> Self {
         self.on_copy = Some(Box::new(on_copy));
         self
     }
 
+    /// Subscribe to errors: failed page and image fetches, unresolvable
+    /// links and engine parse/render failures. Without it errors are logged
+    /// to stderr.
+    pub fn on_error(mut self, on_error: impl Fn(WebViewError) -> Message + 'static) -> Self {
+        self.on_error = Some(Box::new(on_error));
+        self
+    }
+
+    /// Subscribe to the results of [`Action::EvaluateScript`], in the order
+    /// the scripts finish, each with the id it was sent with. Engines
+    /// without JavaScript answer every script with
+    /// [`WebViewError::Unsupported`].
+    pub fn on_script_result(
+        mut self,
+        on_script_result: impl Fn(ScriptId, Result<ScriptValue, WebViewError>) -> Message + 'static,
+    ) -> Self {
+        self.on_script_result = Some(Box::new(on_script_result));
+        self
+    }
+
+    /// Render the page shown when a URL fails to load, e.g. a branded or
+    /// localized page with a retry link to the failed URL. Without it a
+    /// plain built-in page is used.
+    pub fn error_page(
+        mut self,
+        error_page: impl Fn(ViewId, &Url, &WebViewError) -> String + 'static,
+    ) -> Self {
+        self.error_page = Some(Box::new(error_page));
+        self
+    }
+
     /// Provide a mapper from Action to Message so the webview can spawn async
     /// tasks (e.g. URL fetches) that route back through the update loop.
     /// Required for URL navigation on engines that don't handle URLs natively.
@@ -179,32 +282,166 @@ impl<Engine: engines::Engine + Default, Message: Send + Clone + 'static> WebView
         self
     }
 
-    /// Passes update to webview
-    pub fn update(&mut self, action: Action) -> Task<Message> {
-        let mut tasks = Vec::new();
+    /// Choose where page, stylesheet and image fetches are served from.
+    /// Use [`FetchMode::Replay`](crate::FetchMode::Replay) to render a
+    /// recorded site without network access.
+    #[cfg(any(feature = "litehtml", feature = "blitz"))]
+    pub fn fetch_mode(mut self, mode: crate::FetchMode) -> Self {
+        self.fetch.mode = mode;
+        self.engine
+            .set_fetch_policy(self.fetch.mode.clone(), self.fetch.retry.clone());
+        self
+    }
 
-        if self.current_view_index.is_some() {
-            if let Some(on_url_change) = &self.on_url_change {
-                let url = self.engine.get_url(self.get_current_view_id());
-                if self.url != url {
-                    self.url = url.clone();
-                    tasks.push(Task::done(on_url_change(url)))
+    /// Retry transient fetch failures (timeouts, connection failures,
+    /// 502/503/504) with exponential backoff. Defaults to
+    /// [`RetryPolicy::default`](crate::RetryPolicy::default); pass
+    /// [`RetryPolicy::none`](crate::RetryPolicy::none) to fail immediately.
+    #[cfg(any(feature = "litehtml", feature = "blitz"))]
+    pub fn retry_policy(mut self, policy: crate::RetryPolicy) -> Self {
+        self.fetch.retry = policy;
+        self.engine
+            .set_fetch_policy(self.fetch.mode.clone(), self.fetch.retry.clone());
+        self
+    }
+
+    /// Draw litehtml and Blitz documents with the GPU shader widget, like
+    /// Servo and CEF, instead of iced's image cache. Tiles stay uploaded
+    /// and scrolling only moves them. Off by default.
+    #[cfg(any(feature = "litehtml", feature = "blitz"))]
+    pub fn shader_rendering(mut self, enabled: bool) -> Self {
+        self.shader_rendering = enabled;
+        self
+    }
+
+    /// Route an event to `on_event` and the matching convenience callback.
+    /// Errors and script results are reported for every view, and without
+    /// one (`id` of `None`), everything else only for the current view.
+    /// Errors are logged when neither `on_error` nor `on_event` is set.
+    fn dispatch_event(
+        &self,
+        tasks: &mut Vec<Task<Message>>,
+        id: Option<ViewId>,
+        event: WebViewEvent,
+    ) {
+        let current = id.is_some_and(|id| self.is_current_view(id));
+        match &event {
+            WebViewEvent::UrlChanged(url) if current => {
+                if let Some(on_url_change) = &self.on_url_change {
+                    tasks.push(Task::done(on_url_change(url.clone())));
+                }
+            }
+            WebViewEvent::TitleChanged(title) if current => {
+                if let Some(on_title_change) = &self.on_title_change {
+                    tasks.push(Task::done(on_title_change(title.clone())));
                 }
             }
-            if let Some(on_title_change) = &self.on_title_change {
-                let title = self.engine.get_title(self.get_current_view_id());
-                if self.title != title {
-                    self.title = title.clone();
-                    tasks.push(Task::done(on_title_change(title)))
+            WebViewEvent::Error(error) => match &self.on_error {
+                Some(on_error) => tasks.push(Task::done(on_error(error.clone()))),
+                None if self.on_event.is_none() => eprintln!("iced_webview: {error}"),
+                None => {}
+            },
+            WebViewEvent::ScriptResult(script_id, result) => {
+                if let Some(on_script_result) = &self.on_script_result {
+                    tasks.push(Task::done(on_script_result(*script_id, result.clone())));
                 }
             }
+            _ => {}
         }
+        if current
+            || matches!(
+                event,
+                WebViewEvent::Error(_) | WebViewEvent::ScriptResult(..)
+            )
+        {
+            if let Some(on_event) = &self.on_event {
+                tasks.push(Task::done(on_event(event)));
+            }
+        }
+    }
 
+    fn report_error(&self, tasks: &mut Vec<Task<Message>>, id: ViewId, error: WebViewError) {
+        self.dispatch_event(tasks, Some(id), WebViewEvent::Error(error));
+    }
+
+    /// Navigate or scroll for a link the engine reports as clicked.
+    fn follow_anchor_click(&mut self, tasks: &mut Vec<Task<Message>>, id: ViewId) {
+        if let Some(href) = self.engine.take_anchor_click(id) {
+            let current = self.engine.get_url(id);
+            let base = Url::parse(&current).ok();
+            match Url::parse(&href).or_else(|_| {
+                base.as_ref()
+                    .ok_or(url::ParseError::RelativeUrlWithoutBase)
+                    .and_then(|b| b.join(&href))
+            }) {
+                Ok(resolved) => {
+                    let scheme = resolved.scheme();
+                    if scheme == "http" || scheme == "https" {
+                        let is_same_page = base
+                            .as_ref()
+                            .is_some_and(|cur| crate::util::is_same_page(&resolved, cur));
+                        match resolved.fragment().filter(|_| is_same_page) {
+                            Some(fragment) => {
+                                self.engine.scroll_to_fragment(id, fragment);
+                            }
+                            // An error page sits at the URL that failed, so
+                            // its retry link points at the page itself
+                            None if is_same_page && !self.error_pages.contains(&id) => {}
+                            None => tasks.push(self.update(Action::GoToUrl(resolved))),
+                        }
+                    }
+                }
+                Err(e) => {
+                    self.report_error(tasks, id, WebViewError::InvalidUrl(format!("{href}: {e}")));
+                }
+            }
+        }
+    }
+
+    /// Drives the engine; use this instead of a fixed `time::every` timer.
+    ///
+    /// Ticks `Action::Update` every 10 ms while there is work to do: an action
+    /// changed state or the engine reports [`needs_update`](engines::Engine::needs_update).
+    /// Otherwise it stays idle until the engine wakes it.
+    pub fn subscription(&self) -> Subscription<Action> {
+        let wakeups =
+            Subscription::run_with(self.waker.clone(), Waker::wakeups).map(|()| Action::Update);
+        if self.dirty || self.engine.needs_update() {
+            Subscription::batch([
+                wakeups,
+                time::every(super::UPDATE_INTERVAL).map(|_| Action::Update),
+            ])
+        } else {
+            wakeups
+        }
+    }
+
+    /// Passes update to webview
+    pub fn update(&mut self, action: Action) -> Task<Message> {
+        // Owe one more update after anything but an update or a no-op resize
+        self.dirty = !matches!(action, Action::Update | Action::Resize(_));
+
+        let mut tasks = vec![self.apply(action)];
+        // Drained after the action so its own events arrive with it.
+        for EngineEvent { view, event } in self.engine.take_events() {
+            self.dispatch_event(&mut tasks, Some(view), event);
+        }
+        Task::batch(tasks)
+    }
+
+    /// Handle one action, without reporting engine events.
+    fn apply(&mut self, action: Action) -> Task<Message> {
+        let mut tasks = Vec::new();
         match action {
             Action::ChangeView(index) => {
                 self.current_view_index = Some(index as usize);
-                self.engine
-                    .request_render(self.index_as_view_id(index), self.view_size);
+                let id = self.index_as_view_id(index);
+                self.engine.request_render(id, self.view_size);
+                // The callbacks follow the current view, so announce the new one
+                let url = self.engine.get_url(id);
+                self.dispatch_event(&mut tasks, Some(id), WebViewEvent::UrlChanged(url));
+                let title = self.engine.get_title(id);
+                self.dispatch_event(&mut tasks, Some(id), WebViewEvent::TitleChanged(title));
             }
             Action::CloseCurrentView => {
                 let idx = self.current_view_index.expect(
@@ -235,35 +472,29 @@ impl<Engine: engines::Engine + Default, Message: Send + Clone + 'static> WebView
                 }
             }
             Action::CreateView(page_type) => {
-                if let PageType::Url(url) = page_type {
-                    if !self.engine.handles_urls() {
-                        let id = self.engine.new_view(self.view_size, None);
-                        self.view_ids.push(id);
-                        self.engine.goto(id, PageType::Url(url.clone()));
-
-                        #[cfg(any(feature = "litehtml", feature = "blitz"))]
-                        if let Some(mapper) = &self.action_mapper {
-                            let mapper = mapper.clone();
-                            let url_clone = url.clone();
-                            tasks.push(Task::perform(
-                                crate::fetch::fetch_html(url),
-                                move |result| mapper(Action::FetchComplete(id, url_clone, result)),
-                            ));
-                        } else {
-                            eprintln!("iced_webview: on_action() mapper required for URL navigation with this engine");
-                        }
+                let url = match &page_type {
+                    PageType::Url(url) => Some(url.clone()),
+                    PageType::Html(_) => None,
+                };
+                let id = self.engine.new_view(self.view_size, Some(page_type));
+                self.view_ids.push(id);
 
-                        #[cfg(not(any(feature = "litehtml", feature = "blitz")))]
-                        eprintln!("iced_webview: on_action() mapper required for URL navigation with this engine");
+                // Engines without native URL support start blank; fetch the page for them.
+                if let Some(url) = url.filter(|_| !self.engine.view_handles_urls(id)) {
+                    #[cfg(any(feature = "litehtml", feature = "blitz"))]
+                    if let Some(mapper) = &self.action_mapper {
+                        let mapper = mapper.clone();
+                        let url_clone = url.clone();
+                        tasks.push(Task::perform(
+                            crate::fetch::fetch_html(url, self.fetch.clone()),
+                            move |result| mapper(Action::FetchComplete(id, url_clone, result)),
+                        ));
                     } else {
-                        let id = self
-                            .engine
-                            .new_view(self.view_size, Some(PageType::Url(url)));
-                        self.view_ids.push(id);
+                        eprintln!("iced_webview: on_action() mapper required for URL navigation with this engine");
                     }
-                } else {
-                    let id = self.engine.new_view(self.view_size, Some(page_type));
-                    self.view_ids.push(id);
+
+                    #[cfg(not(any(feature = "litehtml", feature = "blitz")))]
+                    eprintln!("iced_webview: no fetch support compiled in to load {url}");
                 }
 
                 if let Some(on_view_create) = &self.on_create_view {
@@ -285,12 +516,12 @@ impl<Engine: engines::Engine + Default, Message: Send + Clone + 'static> WebView
                 self.engine.goto(view_id, PageType::Url(url_str.clone()));
 
                 #[cfg(any(feature = "litehtml", feature = "blitz"))]
-                if !self.engine.handles_urls() {
+                if !self.engine.view_handles_urls(view_id) {
                     if let Some(mapper) = &self.action_mapper {
                         let mapper = mapper.clone();
                         let fetch_url = url_str.clone();
                         tasks.push(Task::perform(
-                            crate::fetch::fetch_html(fetch_url),
+                            crate::fetch::fetch_html(fetch_url, self.fetch.clone()),
                             move |result| mapper(Action::FetchComplete(view_id, url_str, result)),
                         ));
                     } else {
@@ -299,7 +530,7 @@ impl<Engine: engines::Engine + Default, Message: Send + Clone + 'static> WebView
                 }
 
                 #[cfg(not(any(feature = "litehtml", feature = "blitz")))]
-                if !self.engine.handles_urls() {
+                if !self.engine.view_handles_urls(view_id) {
                     eprintln!("iced_webview: on_action() mapper required for URL navigation with this engine");
                 }
             }
@@ -315,34 +546,7 @@ impl<Engine: engines::Engine + Default, Message: Send + Clone + 'static> WebView
                 self.engine.handle_mouse_event(view_id, point, event);
 
                 // Check if the click triggered an anchor navigation
-                if let Some(href) = self.engine.take_anchor_click(view_id) {
-                    let current = self.engine.get_url(view_id);
-                    let base = Url::parse(&current).ok();
-                    match Url::parse(&href).or_else(|_| {
-                        base.as_ref()
-                            .ok_or(url::ParseError::RelativeUrlWithoutBase)
-                            .and_then(|b| b.join(&href))
-                    }) {
-                        Ok(resolved) => {
-                            let scheme = resolved.scheme();
-                            if scheme == "http" || scheme == "https" {
-                                let is_same_page = base
-                                    .as_ref()
-                                    .is_some_and(|cur| crate::util::is_same_page(&resolved, cur));
-                                if is_same_page {
-                                    if let Some(fragment) = resolved.fragment() {
-                                        self.engine.scroll_to_fragment(view_id, fragment);
-                                    }
-                                } else {
-                                    tasks.push(self.update(Action::GoToUrl(resolved)));
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!("iced_webview: failed to resolve anchor URL '{href}': {e}");
-                        }
-                    }
-                }
+                self.follow_anchor_click(&mut tasks, view_id);
 
                 // Don't request_render here — the periodic Update tick handles
                 // it. Re-rendering inline on every mouse event (especially
@@ -361,6 +565,9 @@ impl<Engine: engines::Engine + Default, Message: Send + Clone + 'static> WebView
                     if self.inflight_images == 0 {
                         self.engine.flush_staged_images(view_id, self.view_size);
                     }
+
+                    // Engines that render off-thread report clicks a tick later
+                    self.follow_anchor_click(&mut tasks, view_id);
                 }
 
                 // Discover images that need fetching after layout
@@ -374,10 +581,14 @@ impl<Engine: engines::Engine + Default, Message: Send + Clone + 'static> WebView
                         let resolved = crate::util::resolve_url(&src, &baseurl, &page_url);
                         let resolved = match resolved {
                             Ok(u) => u,
-                            Err(_) => continue,
+                            Err(_) => {
+                                self.engine.image_fetch_failed(view_id, &src);
+                                continue;
+                            }
                         };
                         let scheme = resolved.scheme();
                         if scheme != "http" && scheme != "https" {
+                            self.engine.image_fetch_failed(view_id, &src);
                             continue;
                         }
                         self.inflight_images += 1;
@@ -385,7 +596,7 @@ impl<Engine: engines::Engine + Default, Message: Send + Clone + 'static> WebView
                         let raw_src = src.clone();
                         let epoch = *self.nav_epochs.get(&view_id).unwrap_or(&0);
                         tasks.push(Task::perform(
-                            crate::fetch::fetch_image(resolved.to_string()),
+                            crate::fetch::fetch_image(resolved.to_string(), self.fetch.clone()),
                             move |result| {
                                 mapper(Action::ImageFetchComplete(
                                     view_id,
@@ -405,6 +616,7 @@ impl<Engine: engines::Engine + Default, Message: Send + Clone + 'static> WebView
                 if self.view_size != size {
                     self.view_size = size;
                     self.engine.resize(size);
+                    self.dirty = true;
                 } else {
                     // No-op resize (published every frame because the widget
                     // is recreated with bounds 0,0). Skip request_render to
@@ -422,22 +634,39 @@ impl<Engine: engines::Engine + Default, Message: Send + Clone + 'static> WebView
                 }
                 return Task::batch(tasks);
             }
+            Action::EvaluateScript(script_id, script) => {
+                let view_id = self.current_view();
+                let result = match view_id {
+                    Some(id) => self.engine.evaluate_script(id, script_id, script),
+                    None => Err(WebViewError::Script("no view to run the script in".into())),
+                };
+                if let Err(error) = result {
+                    let event = WebViewEvent::ScriptResult(script_id, Err(error));
+                    self.dispatch_event(&mut tasks, view_id, event);
+                }
+                return Task::batch(tasks);
+            }
             Action::FetchComplete(view_id, url, result) => {
                 if !self.engine.has_view(view_id) {
                     return Task::batch(tasks);
                 }
                 match result {
                     Ok((html, css_cache)) => {
+                        self.error_pages.remove(&view_id);
                         self.engine.set_css_cache(view_id, css_cache);
                         self.engine.goto(view_id, PageType::Html(html));
                     }
                     Err(e) => {
-                        let error_html = format!(
-                            "<html><body><h1>Failed to load</h1><p>{}</p><p>{}</p></body></html>",
-                            crate::util::html_escape(&url),
-                            crate::util::html_escape(&e),
-                        );
+                        let custom = self
+                            .error_page
+                            .as_ref()
+                            .zip(Url::parse(&url).ok())
+                            .map(|(hook, url)| hook(view_id, &url, &e));
+                        let error_html =
+                            custom.unwrap_or_else(|| crate::util::default_error_page(&url, &e));
                         self.engine.goto(view_id, PageType::Html(error_html));
+                        self.error_pages.insert(view_id);
+                        self.report_error(&mut tasks, view_id, e);
                     }
                 }
             }
@@ -459,7 +688,12 @@ impl<Engine: engines::Engine + Default, Message: Send + Clone + 'static> WebView
                             );
                         }
                         Err(e) => {
-                            eprintln!("iced_webview: failed to fetch image '{}': {}", src, e);
+                            self.engine.image_fetch_failed(view_id, &src);
+                            let error = WebViewError::Image {
+                                url: src.clone(),
+                                error: Box::new(e.clone()),
+                            };
+                            self.report_error(&mut tasks, view_id, error);
                         }
                     }
                 }
@@ -482,52 +716,94 @@ impl<Engine: engines::Engine + Default, Message: Send + Clone + 'static> WebView
         let id = self.get_current_view_id();
         let content_height = self.engine.get_content_height(id);
 
-        if content_height > 0.0 {
-            // Engines that render a full-document buffer (blitz, litehtml):
-            // use the image Handle widget with y-offset scrolling.
-            WebViewWidget::new(
-                self.engine.get_view(id),
-                self.engine.get_cursor(id),
-                self.engine.get_selection_rects(id),
-                self.engine.get_scroll_y(id),
-                content_height,
-                self.scale_factor,
-            )
-            .into()
-        } else {
-            // Engines that manage their own scrolling and produce a viewport-
-            // sized frame each tick (servo): use the shader widget for direct
-            // GPU texture updates, avoiding Handle cache churn.
-            #[cfg(any(feature = "servo", feature = "cef"))]
-            {
-                use crate::webview::shader_widget::WebViewShaderProgram;
-                iced::widget::Shader::new(WebViewShaderProgram::new(
-                    self.engine.get_view(id),
+        // Engines that manage their own scrolling and produce a viewport-
+        // sized frame each tick (servo, cef) use the shader widget for direct
+        // GPU texture updates, avoiding Handle cache churn. Full-document
+        // engines (blitz, litehtml) use it when `shader_rendering` is set.
+        #[cfg(any(
+            feature = "litehtml",
+            feature = "blitz",
+            feature = "servo",
+            feature = "cef"
+        ))]
+        {
+            use crate::webview::shader_widget::{Content, WebViewShaderProgram};
+            #[cfg(any(feature = "litehtml", feature = "blitz"))]
+            let documents = self.shader_rendering;
+            #[cfg(not(any(feature = "litehtml", feature = "blitz")))]
+            let documents = false;
+
+            let content = if content_height > 0.0 {
+                documents.then(|| {
+                    Content::document(
+                        id,
+                        self.engine.get_view(id),
+                        self.engine.get_tiles(id),
+                        self.engine.get_scroll_y(id),
+                        content_height,
+                        self.engine.get_selection_rects(id),
+                    )
+                })
+            } else {
+                cfg!(any(feature = "servo", feature = "cef"))
+                    .then(|| Content::frame(id, self.engine.get_view(id)))
+            };
+            if let Some(content) = content {
+                return iced::widget::Shader::new(WebViewShaderProgram::new(
+                    content,
                     self.engine.get_cursor(id),
                 ))
                 .width(Length::Fill)
                 .height(Length::Fill)
-                .into()
-            }
-            #[cfg(not(any(feature = "servo", feature = "cef")))]
-            {
-                WebViewWidget::new(
-                    self.engine.get_view(id),
-                    self.engine.get_cursor(id),
-                    self.engine.get_selection_rects(id),
-                    0.0,
-                    0.0,
-                    self.scale_factor,
-                )
-                .into()
+                .into();
             }
         }
+
+        // Otherwise the image Handle widget, with y-offset scrolling for
+        // full-document engines.
+        WebViewWidget::new(
+            self.engine.get_view(id),
+            self.engine.get_cursor(id),
+            self.engine.get_selection_rects(id),
+            self.engine.get_tiles(id),
+            self.engine.get_scroll_y(id),
+            content_height,
+            self.scale_factor,
+        )
+        .into()
     }
 
     /// Get the current view's image info for direct rendering
     pub fn current_image(&self) -> &crate::ImageInfo {
         self.engine.get_view(self.get_current_view_id())
     }
+
+    /// What the engine showing the current view supports, e.g. to hide a
+    /// Back button. Without a view, what the engine as a whole supports.
+    pub fn capabilities(&self) -> crate::Capabilities {
+        match self.current_view() {
+            Some(id) => self.engine.view_capabilities(id),
+            None => self.engine.capabilities(),
+        }
+    }
+
+    /// Whether the current view has history to go back to.
+    pub fn can_go_back(&self) -> bool {
+        self.current_view()
+            .is_some_and(|id| self.engine.can_go_back(id))
+    }
+
+    /// Whether the current view has history to go forward to.
+    pub fn can_go_forward(&self) -> bool {
+        self.current_view()
+            .is_some_and(|id| self.engine.can_go_forward(id))
+    }
+
+    /// Whether the current view is still loading.
+    pub fn is_loading(&self) -> bool {
+        self.current_view()
+            .is_some_and(|id| self.engine.is_loading(id))
+    }
 }
 
 struct WebViewWidget<'a> {
@@ -535,6 +811,7 @@ struct WebViewWidget<'a> {
     cursor: Interaction,
     bounds: Size<u32>,
     selection_rects: &'a [[f32; 4]],
+    tiles: &'a [Tile],
     scroll_y: f32,
     content_height: f32,
     scale_factor: f32,
@@ -545,6 +822,7 @@ impl<'a> WebViewWidget<'a> {
         image_info: &ImageInfo,
         cursor: Interaction,
         selection_rects: &'a [[f32; 4]],
+        tiles: &'a [Tile],
         scroll_y: f32,
         content_height: f32,
         scale_factor: f32,
@@ -554,6 +832,7 @@ impl<'a> WebViewWidget<'a> {
             cursor,
             bounds: Size::new(0, 0),
             selection_rects,
+            tiles,
             scroll_y,
             content_height,
             scale_factor,
@@ -600,6 +879,23 @@ where
             // the image to fit the smaller logical rectangle.
             let s = self.scale_factor;
             renderer.with_layer(bounds, |renderer| {
+                // Tiling engines only rasterize the bands around the viewport
+                for tile in self.tiles {
+                    let tile_bounds = Rectangle {
+                        x: bounds.x,
+                        y: bounds.y + (tile.top() - self.scroll_y) * s,
+                        width: bounds.width,
+                        height: tile.height() * s,
+                    };
+                    renderer.draw_image(
+                        core_image::Image::new(tile.image().as_handle()).snap(true),
+                        tile_bounds,
+                        *viewport,
+                    );
+                }
+                if !self.tiles.is_empty() {
+                    return;
+                }
                 let image_bounds = Rectangle {
                     x: bounds.x,
                     y: bounds.y - self.scroll_y * s,
@@ -626,7 +922,6 @@ where
             let rects = self.selection_rects;
             let scroll_y = self.scroll_y;
             renderer.with_layer(bounds, |renderer| {
-                let highlight = iced::Color::from_rgba(0.26, 0.52, 0.96, 0.3);
                 for rect in rects {
                     let quad_bounds = Rectangle {
                         x: bounds.x + rect[0],
@@ -639,7 +934,7 @@ where
                             bounds: quad_bounds,
                             ..renderer::Quad::default()
                         },
-                        highlight,
+                        crate::webview::SELECTION_HIGHLIGHT,
                     );
                 }
             });
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::collections::HashMap;
 
 use iced::mouse::{self, Interaction};
 use iced::wgpu;
@@ -6,21 +6,92 @@ use iced::widget::shader;
 use iced::{keyboard, Event, Point, Rectangle, Size};
 
 use crate::webview::basic::Action;
-use crate::ImageInfo;
+use crate::webview::SELECTION_HIGHLIGHT;
+use crate::{ImageInfo, PixelFormat, Tile, ViewId};
 
-/// Shader-based rendering for servo webview content.
+/// Shader-based rendering for webview content.
 ///
 /// Uses direct GPU texture updates (`queue.write_texture()`) instead of iced's
 /// image Handle cache, avoiding the texture allocation churn and visible
 /// flickering that happens during rapid frame updates (e.g. scrolling).
+/// Frames are uploaded in the engine's [`PixelFormat`]; the fragment shader
+/// swizzles BGRA and unpremultiplies alpha.
+///
+/// Servo and CEF frames fill the widget. litehtml and Blitz documents are
+/// drawn tile by tile at their offset from the scroll position, so scrolling
+/// only moves textures that are already uploaded. Frames larger than the
+/// GPU's texture limit, such as a tall page without tiles, are split across
+/// several textures.
 pub struct WebViewShaderProgram<'a> {
-    image_info: &'a ImageInfo,
+    content: Content<'a>,
     cursor: Interaction,
 }
 
 impl<'a> WebViewShaderProgram<'a> {
-    pub fn new(image_info: &'a ImageInfo, cursor: Interaction) -> Self {
-        Self { image_info, cursor }
+    pub fn new(content: Content<'a>, cursor: Interaction) -> Self {
+        Self { content, cursor }
+    }
+}
+
+/// What a shader widget shows for one view.
+pub(crate) struct Content<'a> {
+    view_id: ViewId,
+    frame: &'a ImageInfo,
+    tiles: &'a [Tile],
+    scroll_y: f32,
+    content_height: f32,
+    selection_rects: &'a [[f32; 4]],
+}
+
+impl<'a> Content<'a> {
+    /// A frame that fills the widget, for engines that manage their own
+    /// scrolling (servo, cef).
+    pub(crate) fn frame(view_id: ViewId, frame: &'a ImageInfo) -> Self {
+        Self {
+            view_id,
+            frame,
+            tiles: &[],
+            scroll_y: 0.0,
+            content_height: 0.0,
+            selection_rects: &[],
+        }
+    }
+
+    /// A document scrolled by `scroll_y`, drawn from its tiles or, without
+    /// tiles, from a full-page `frame` (blitz, litehtml).
+    pub(crate) fn document(
+        view_id: ViewId,
+        frame: &'a ImageInfo,
+        tiles: &'a [Tile],
+        scroll_y: f32,
+        content_height: f32,
+        selection_rects: &'a [[f32; 4]],
+    ) -> Self {
+        Self {
+            view_id,
+            frame,
+            tiles,
+            scroll_y,
+            content_height,
+            selection_rects,
+        }
+    }
+
+    /// The primitive drawing this content into `bounds`.
+    pub(crate) fn primitive(&self, bounds: Rectangle) -> WebViewPrimitive {
+        let layers = if !self.tiles.is_empty() {
+            self.tiles.to_vec()
+        } else if self.content_height > 0.0 {
+            vec![Tile::new(0.0, self.content_height, self.frame.clone())]
+        } else {
+            vec![Tile::new(0.0, bounds.height, self.frame.clone())]
+        };
+        WebViewPrimitive {
+            view_id: self.view_id,
+            layers,
+            scroll_y: self.scroll_y,
+            selection: self.selection_rects.to_vec(),
+        }
     }
 }
 
@@ -30,37 +101,104 @@ pub struct ShaderState {
 }
 
 pub struct WebViewPrimitive {
-    pub(crate) pixels: Arc<Vec<u8>>,
-    pub(crate) width: u32,
-    pub(crate) height: u32,
+    /// Selects the view's textures in the shared pipeline.
+    view_id: ViewId,
+    /// Frames placed by their offset from the top of the document; cloning
+    /// their `ImageInfo` shares the pixels.
+    layers: Vec<Tile>,
+    scroll_y: f32,
+    /// Selection highlights in document coordinates.
+    selection: Vec<[f32; 4]>,
 }
 
 impl std::fmt::Debug for WebViewPrimitive {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("WebViewPrimitive")
-            .field("width", &self.width)
-            .field("height", &self.height)
+            .field("view_id", &self.view_id)
+            .field("layers", &self.layers.len())
+            .field("scroll_y", &self.scroll_y)
+            .field("selection", &self.selection.len())
             .finish()
     }
 }
 
+/// Layer textures kept per view, so tiles scrolled out of the viewport and
+/// back in are not uploaded again.
+const MAX_LAYER_TEXTURES: usize = 8;
+
+/// One pipeline is shared by every shader widget in the application, so each
+/// view gets its own textures and uniforms. Views that were not drawn since
+/// the last [`trim`](shader::Pipeline::trim) are evicted.
 pub struct WebViewPipeline {
-    texture: wgpu::Texture,
-    texture_view: wgpu::TextureView,
     sampler: wgpu::Sampler,
     bind_group_layout: wgpu::BindGroupLayout,
-    bind_group: wgpu::BindGroup,
     render_pipeline: wgpu::RenderPipeline,
-    texture_size: (u32, u32),
+    selection_pipeline: wgpu::RenderPipeline,
+    max_texture_size: u32,
+    views: HashMap<ViewId, ViewTextures>,
+}
+
+/// GPU resources of one view.
+#[derive(Default)]
+struct ViewTextures {
+    layers: Vec<LayerTexture>,
+    /// Index into `layers` of each layer prepared for drawing, in order.
+    order: Vec<usize>,
+    /// Instance buffer of the selection highlights.
+    selection: Option<wgpu::Buffer>,
+    /// Prepare passes so far; stamps `Slot::last_used`.
+    pass: u64,
+    /// Drawn since the last trim.
+    used: bool,
+}
+
+/// The texture and uniforms of one layer, or of one region of a layer too
+/// large for a single texture.
+struct LayerTexture {
+    texture: wgpu::Texture,
+    params: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    slot: Slot,
 }
 
-impl WebViewPipeline {
-    fn recreate_texture(&mut self, device: &wgpu::Device, width: u32, height: u32) {
-        let (texture, texture_view) = create_texture(device, width.max(1), height.max(1));
+/// What a [`LayerTexture`] holds, apart from its GPU objects.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Slot {
+    /// Region of the image in the texture; its size is the texture's.
+    region: Rectangle<u32>,
+    /// [`ImageInfo::frame`] of the pixels in the texture, if any.
+    frame: Option<u64>,
+    /// The last prepare pass that drew it.
+    last_used: u64,
+}
+
+impl Slot {
+    /// Whether the texture already holds `region` of `image`.
+    fn holds(&self, image: &ImageInfo, region: Rectangle<u32>) -> bool {
+        self.region == region && self.frame == Some(image.frame())
+    }
+}
+
+impl LayerTexture {
+    fn new(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        sampler: &wgpu::Sampler,
+        region: Rectangle<u32>,
+    ) -> Self {
+        let (texture, texture_view) =
+            create_texture(device, region.width.max(1), region.height.max(1));
+
+        let params = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("webview_params"),
+            size: PARAMS_SIZE,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
 
-        self.bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("webview_bind_group"),
-            layout: &self.bind_group_layout,
+            layout,
             entries: &[
                 wgpu::BindGroupEntry {
                     binding: 0,
@@ -68,15 +206,177 @@ impl WebViewPipeline {
                 },
                 wgpu::BindGroupEntry {
                     binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: params.as_entire_binding(),
                 },
             ],
         });
 
-        self.texture = texture;
-        self.texture_view = texture_view;
-        self.texture_size = (width, height);
+        Self {
+            texture,
+            params,
+            bind_group,
+            slot: Slot {
+                region,
+                frame: None,
+                last_used: 0,
+            },
+        }
     }
+
+    /// Copy `region` of `image` into the texture: only the parts that
+    /// changed since the frame it holds, when the engine reported them,
+    /// otherwise all of it.
+    fn upload(&mut self, queue: &wgpu::Queue, image: &ImageInfo, region: Rectangle<u32>) {
+        let stride = image.image_width();
+        let pixels = image.raw_pixels();
+        let whole = [region];
+        let rects = self
+            .slot
+            .frame
+            .filter(|_| self.slot.region == region)
+            .and_then(|base| image.damage_since(base))
+            .unwrap_or(&whole);
+        for rect in rects {
+            let Some(rect) = intersect(rect, &region) else {
+                continue;
+            };
+            queue.write_texture(
+                wgpu::TexelCopyTextureInfo {
+                    texture: &self.texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d {
+                        x: rect.x - region.x,
+                        y: rect.y - region.y,
+                        z: 0,
+                    },
+                    aspect: wgpu::TextureAspect::All,
+                },
+                &pixels,
+                wgpu::TexelCopyBufferLayout {
+                    offset: 4 * (rect.y as u64 * stride as u64 + rect.x as u64),
+                    bytes_per_row: Some(4 * stride),
+                    rows_per_image: Some(rect.height),
+                },
+                wgpu::Extent3d {
+                    width: rect.width,
+                    height: rect.height,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+        self.slot.region = region;
+        self.slot.frame = Some(image.frame());
+    }
+}
+
+impl ViewTextures {
+    /// The texture for `region` of `image`, picked by [`pick_slot`] or
+    /// created when none fits.
+    fn layer_for(
+        &mut self,
+        image: &ImageInfo,
+        region: Rectangle<u32>,
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        sampler: &wgpu::Sampler,
+    ) -> usize {
+        let slots: Vec<Slot> = self.layers.iter().map(|layer| layer.slot).collect();
+        let index = pick_slot(&slots, image, region, self.pass).unwrap_or_else(|| {
+            self.layers
+                .push(LayerTexture::new(device, layout, sampler, region));
+            self.layers.len() - 1
+        });
+        self.layers[index].slot.last_used = self.pass;
+        index
+    }
+
+    /// Drop the least recently used textures beyond [`MAX_LAYER_TEXTURES`].
+    fn evict(&mut self) {
+        while self.layers.len() > MAX_LAYER_TEXTURES {
+            let Some(oldest) = self
+                .layers
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, layer)| layer.slot.last_used)
+                .map(|(index, _)| index)
+            else {
+                break;
+            };
+            self.layers.swap_remove(oldest);
+        }
+    }
+}
+
+/// Pick the texture for `region` of `image`: the one already holding it,
+/// else one holding the same region of the frame its damage is relative to,
+/// else the least recently used one of the same size. Textures picked
+/// earlier in this `pass` are skipped.
+fn pick_slot(
+    slots: &[Slot],
+    image: &ImageInfo,
+    region: Rectangle<u32>,
+    pass: u64,
+) -> Option<usize> {
+    let free = |slot: &Slot| {
+        slot.last_used != pass
+            && (slot.region.width, slot.region.height) == (region.width, region.height)
+    };
+    slots
+        .iter()
+        .position(|slot| free(slot) && slot.holds(image, region))
+        .or_else(|| {
+            slots.iter().position(|slot| {
+                free(slot)
+                    && slot.region == region
+                    && slot
+                        .frame
+                        .is_some_and(|base| image.damage_since(base).is_some())
+            })
+        })
+        .or_else(|| {
+            slots
+                .iter()
+                .enumerate()
+                .filter(|(_, slot)| free(slot))
+                .min_by_key(|(_, slot)| slot.last_used)
+                .map(|(index, _)| index)
+        })
+}
+
+/// Split a `width` by `height` image into regions no larger than `max` on
+/// either side, row by row.
+fn split(width: u32, height: u32, max: u32) -> Vec<Rectangle<u32>> {
+    let max = max.max(1);
+    let mut regions = Vec::new();
+    for y in (0..height).step_by(max as usize) {
+        for x in (0..width).step_by(max as usize) {
+            regions.push(Rectangle {
+                x,
+                y,
+                width: max.min(width - x),
+                height: max.min(height - y),
+            });
+        }
+    }
+    regions
+}
+
+/// The part of `a` inside `b`, if not empty.
+fn intersect(a: &Rectangle<u32>, b: &Rectangle<u32>) -> Option<Rectangle<u32>> {
+    let x = a.x.max(b.x);
+    let y = a.y.max(b.y);
+    let right = a.x.saturating_add(a.width).min(b.x + b.width);
+    let bottom = a.y.saturating_add(a.height).min(b.y + b.height);
+    (right > x && bottom > y).then(|| Rectangle {
+        x,
+        y,
+        width: right - x,
+        height: bottom - y,
+    })
 }
 
 fn create_texture(
@@ -102,6 +402,25 @@ fn create_texture(
     (texture, view)
 }
 
+/// Size of the shader's `Params` uniform: two `u32` flags, padding, and the
+/// layer's clip-space rectangle.
+const PARAMS_SIZE: u64 = 32;
+
+/// Encode the shader's `Params` uniform for a layer in `format` drawn at
+/// `rect` (left, top, right, bottom in clip space).
+fn layer_params(format: PixelFormat, rect: [f32; 4]) -> [u8; PARAMS_SIZE as usize] {
+    let mut params = [0; PARAMS_SIZE as usize];
+    params[0..4].copy_from_slice(&u32::from(format.is_bgra()).to_ne_bytes());
+    params[4..8].copy_from_slice(&u32::from(format.is_premultiplied()).to_ne_bytes());
+    for (i, value) in rect.iter().enumerate() {
+        params[16 + 4 * i..20 + 4 * i].copy_from_slice(&value.to_ne_bytes());
+    }
+    params
+}
+
+/// Size of one selection instance: clip-space rectangle and linear colour.
+const SELECTION_STRIDE: u64 = 32;
+
 // -- Primitive ----------------------------------------------------------------
 
 impl shader::Primitive for WebViewPrimitive {
@@ -112,44 +431,109 @@ impl shader::Primitive for WebViewPrimitive {
         pipeline: &mut Self::Pipeline,
         device: &wgpu::Device,
         queue: &wgpu::Queue,
-        _bounds: &Rectangle,
+        bounds: &Rectangle,
         _viewport: &shader::Viewport,
     ) {
-        if (self.width, self.height) != pipeline.texture_size {
-            pipeline.recreate_texture(device, self.width, self.height);
+        let WebViewPipeline {
+            sampler,
+            bind_group_layout,
+            max_texture_size,
+            views,
+            ..
+        } = pipeline;
+        let view = views.entry(self.view_id).or_default();
+        view.used = true;
+        view.pass += 1;
+        view.order.clear();
+        view.evict();
+
+        if bounds.width <= 0.0 || bounds.height <= 0.0 {
+            return;
         }
+        // Document coordinates (logical pixels) to clip space.
+        let to_x = |x: f32| x / bounds.width * 2.0 - 1.0;
+        let to_y = |y: f32| 1.0 - (y - self.scroll_y) / bounds.height * 2.0;
+
+        for layer in &self.layers {
+            let image = layer.image();
+            let (width, height) = (image.image_width(), image.image_height());
+            let expected_len = 4 * width as usize * height as usize;
+            if width == 0 || height == 0 || image.raw_pixels().len() != expected_len {
+                continue;
+            }
 
-        let expected_len = 4 * self.width as usize * self.height as usize;
-        if self.pixels.len() == expected_len && self.width > 0 && self.height > 0 {
-            queue.write_texture(
-                wgpu::TexelCopyTextureInfo {
-                    texture: &pipeline.texture,
-                    mip_level: 0,
-                    origin: wgpu::Origin3d::ZERO,
-                    aspect: wgpu::TextureAspect::All,
-                },
-                &self.pixels,
-                wgpu::TexelCopyBufferLayout {
-                    offset: 0,
-                    bytes_per_row: Some(4 * self.width),
-                    rows_per_image: Some(self.height),
-                },
-                wgpu::Extent3d {
-                    width: self.width,
-                    height: self.height,
-                    depth_or_array_layers: 1,
-                },
-            );
+            // Image pixels to document coordinates.
+            let (scale_x, scale_y) = (bounds.width / width as f32, layer.height() / height as f32);
+            for region in split(width, height, *max_texture_size) {
+                let index = view.layer_for(image, region, device, bind_group_layout, sampler);
+                let texture = &mut view.layers[index];
+                // Redraws and scrolling without a new frame upload nothing.
+                if !texture.slot.holds(image, region) {
+                    texture.upload(queue, image, region);
+                }
+                let rect = [
+                    to_x(region.x as f32 * scale_x),
+                    to_y(layer.top() + region.y as f32 * scale_y),
+                    to_x((region.x + region.width) as f32 * scale_x),
+                    to_y(layer.top() + (region.y + region.height) as f32 * scale_y),
+                ];
+                queue.write_buffer(&texture.params, 0, &layer_params(image.format(), rect));
+                view.order.push(index);
+            }
+        }
+
+        if self.selection.is_empty() {
+            return;
+        }
+        let color = SELECTION_HIGHLIGHT.into_linear();
+        let mut instances = Vec::with_capacity(self.selection.len() * SELECTION_STRIDE as usize);
+        for rect in &self.selection {
+            let corners = [
+                to_x(rect[0]),
+                to_y(rect[1]),
+                to_x(rect[0] + rect[2]),
+                to_y(rect[1] + rect[3]),
+            ];
+            for value in corners.iter().chain(&color) {
+                instances.extend_from_slice(&value.to_ne_bytes());
+            }
+        }
+        let len = instances.len() as u64;
+        if view
+            .selection
+            .as_ref()
+            .is_none_or(|buffer| buffer.size() < len)
+        {
+            view.selection = Some(device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("webview_selection"),
+                size: len.next_power_of_two(),
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }));
+        }
+        if let Some(buffer) = &view.selection {
+            queue.write_buffer(buffer, 0, &instances);
         }
     }
 
     fn draw(&self, pipeline: &Self::Pipeline, render_pass: &mut wgpu::RenderPass<'_>) -> bool {
-        if self.width == 0 || self.height == 0 {
+        let Some(view) = pipeline.views.get(&self.view_id) else {
             return true;
+        };
+
+        if !view.order.is_empty() {
+            render_pass.set_pipeline(&pipeline.render_pipeline);
+            for &index in &view.order {
+                render_pass.set_bind_group(0, &view.layers[index].bind_group, &[]);
+                render_pass.draw(0..6, 0..1);
+            }
+        }
+
+        if let (false, Some(buffer)) = (self.selection.is_empty(), &view.selection) {
+            render_pass.set_pipeline(&pipeline.selection_pipeline);
+            render_pass.set_vertex_buffer(0, buffer.slice(..));
+            render_pass.draw(0..6, 0..self.selection.len() as u32);
         }
-        render_pass.set_pipeline(&pipeline.render_pipeline);
-        render_pass.set_bind_group(0, &pipeline.bind_group, &[]);
-        render_pass.draw(0..3, 0..1);
         true
     }
 }
@@ -158,8 +542,6 @@ impl shader::Primitive for WebViewPrimitive {
 
 impl shader::Pipeline for WebViewPipeline {
     fn new(device: &wgpu::Device, _queue: &wgpu::Queue, format: wgpu::TextureFormat) -> Self {
-        let (texture, texture_view) = create_texture(device, 1, 1);
-
         let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
             label: Some("webview_sampler"),
             mag_filter: wgpu::FilterMode::Linear,
@@ -186,20 +568,15 @@ impl shader::Pipeline for WebViewPipeline {
                     ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
                     count: None,
                 },
-            ],
-        });
-
-        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("webview_bind_group"),
-            layout: &bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&texture_view),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&sampler),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
                 },
             ],
         });
@@ -244,16 +621,59 @@ impl shader::Pipeline for WebViewPipeline {
             cache: None,
         });
 
+        let selection_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("webview_selection_layout"),
+            bind_group_layouts: &[],
+            push_constant_ranges: &[],
+        });
+
+        let selection_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("webview_selection_pipeline"),
+            layout: Some(&selection_layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: Some("vs_selection"),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: SELECTION_STRIDE,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: &wgpu::vertex_attr_array![0 => Float32x4, 1 => Float32x4],
+                }],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: Some("fs_selection"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
         Self {
-            texture,
-            texture_view,
             sampler,
             bind_group_layout,
-            bind_group,
             render_pipeline,
-            texture_size: (1, 1),
+            selection_pipeline,
+            max_texture_size: device.limits().max_texture_dimension_2d,
+            views: HashMap::new(),
         }
     }
+
+    fn trim(&mut self) {
+        // Closed views stop drawing; free their textures.
+        self.views.retain(|_, view| std::mem::take(&mut view.used));
+    }
 }
 
 // -- Program ------------------------------------------------------------------
@@ -313,13 +733,9 @@ impl<'a> shader::Program<Action> for WebViewShaderProgram<'a> {
         &self,
         _state: &Self::State,
         _cursor: mouse::Cursor,
-        _bounds: Rectangle,
+        bounds: Rectangle,
     ) -> Self::Primitive {
-        WebViewPrimitive {
-            pixels: self.image_info.pixels(),
-            width: self.image_info.image_width(),
-            height: self.image_info.image_height(),
-        }
+        self.content.primitive(bounds)
     }
 
     fn mouse_interaction(
@@ -335,27 +751,246 @@ impl<'a> shader::Program<Action> for WebViewShaderProgram<'a> {
 // -- WGSL Shader --------------------------------------------------------------
 
 const SHADER_SOURCE: &str = r#"
+struct Params {
+    bgra: u32,
+    premultiplied: u32,
+    // Where the layer is drawn in clip space: left, top, right, bottom.
+    rect: vec4<f32>,
+};
+
 struct VertexOutput {
     @builtin(position) position: vec4<f32>,
     @location(0) uv: vec2<f32>,
 };
 
+@group(0) @binding(0) var t_texture: texture_2d<f32>;
+@group(0) @binding(1) var t_sampler: sampler;
+@group(0) @binding(2) var<uniform> params: Params;
+
+// Corners of a quad as two triangles, top-left first.
+fn corner(vi: u32) -> vec2<f32> {
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(0.0, 0.0),
+        vec2<f32>(1.0, 0.0),
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(1.0, 0.0),
+        vec2<f32>(1.0, 1.0),
+    );
+    return corners[vi];
+}
+
 @vertex
 fn vs_main(@builtin(vertex_index) vi: u32) -> VertexOutput {
-    // Fullscreen triangle: 3 vertices covering [-1,3] in clip space
     var out: VertexOutput;
-    let x = f32(i32(vi & 1u)) * 4.0 - 1.0;
-    let y = f32(i32(vi >> 1u)) * 4.0 - 1.0;
-    out.position = vec4<f32>(x, y, 0.0, 1.0);
-    out.uv = vec2<f32>((x + 1.0) * 0.5, (1.0 - y) * 0.5);
+    let c = corner(vi);
+    out.position = vec4<f32>(mix(params.rect.xy, params.rect.zw, c), 0.0, 1.0);
+    out.uv = c;
     return out;
 }
 
-@group(0) @binding(0) var t_texture: texture_2d<f32>;
-@group(0) @binding(1) var t_sampler: sampler;
+fn to_srgb(c: vec3<f32>) -> vec3<f32> {
+    let low = c * 12.92;
+    let high = 1.055 * pow(c, vec3<f32>(1.0 / 2.4)) - 0.055;
+    return select(high, low, c <= vec3<f32>(0.0031308));
+}
+
+fn to_linear(c: vec3<f32>) -> vec3<f32> {
+    let low = c / 12.92;
+    let high = pow((c + 0.055) / 1.055, vec3<f32>(2.4));
+    return select(high, low, c <= vec3<f32>(0.04045));
+}
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    return textureSample(t_texture, t_sampler, in.uv);
+    var c = textureSample(t_texture, t_sampler, in.uv);
+    if (params.bgra != 0u) {
+        c = c.bgra;
+    }
+    // The sRGB texture decodes the stored bytes, which were premultiplied
+    // in sRGB space, so divide there and decode again.
+    if (params.premultiplied != 0u && c.a > 0.0) {
+        c = vec4<f32>(to_linear(min(to_srgb(c.rgb) / c.a, vec3<f32>(1.0))), c.a);
+    }
+    return c;
+}
+
+struct SelectionOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+};
+
+@vertex
+fn vs_selection(
+    @builtin(vertex_index) vi: u32,
+    @location(0) rect: vec4<f32>,
+    @location(1) color: vec4<f32>,
+) -> SelectionOutput {
+    var out: SelectionOutput;
+    out.position = vec4<f32>(mix(rect.xy, rect.zw, corner(vi)), 0.0, 1.0);
+    out.color = color;
+    return out;
+}
+
+@fragment
+fn fs_selection(in: SelectionOutput) -> @location(0) vec4<f32> {
+    return in.color;
 }
 "#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(x: u32, y: u32, width: u32, height: u32) -> Rectangle<u32> {
+        Rectangle {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn slot(region: Rectangle<u32>, frame: Option<u64>, last_used: u64) -> Slot {
+        Slot {
+            region,
+            frame,
+            last_used,
+        }
+    }
+
+    fn bounds(width: f32, height: f32) -> Rectangle {
+        Rectangle::new(Point::ORIGIN, Size::new(width, height))
+    }
+
+    fn layers(primitive: &WebViewPrimitive) -> Vec<(f32, f32, u64)> {
+        primitive
+            .layers
+            .iter()
+            .map(|tile| (tile.top(), tile.height(), tile.image().frame()))
+            .collect()
+    }
+
+    #[test]
+    fn documents_draw_their_tiles() {
+        let frame = ImageInfo::blank(10, 10);
+        let tiles = [
+            Tile::new(0.0, 512.0, ImageInfo::blank(10, 512)),
+            Tile::new(512.0, 512.0, ImageInfo::blank(10, 512)),
+        ];
+        let content = Content::document(1, &frame, &tiles, 300.0, 1024.0, &[]);
+        let primitive = content.primitive(bounds(10.0, 100.0));
+
+        assert_eq!(
+            layers(&primitive),
+            [
+                (0.0, 512.0, tiles[0].image().frame()),
+                (512.0, 512.0, tiles[1].image().frame()),
+            ]
+        );
+        assert_eq!(primitive.scroll_y, 300.0);
+    }
+
+    #[test]
+    fn documents_without_tiles_fall_back_to_the_whole_page() {
+        let frame = ImageInfo::blank(10, 3000);
+        let content = Content::document(1, &frame, &[], 0.0, 3000.0, &[]);
+        let primitive = content.primitive(bounds(10.0, 100.0));
+        assert_eq!(layers(&primitive), [(0.0, 3000.0, frame.frame())]);
+
+        // Nothing laid out yet: the frame fills the widget
+        let content = Content::document(1, &frame, &[], 0.0, 0.0, &[]);
+        let primitive = content.primitive(bounds(10.0, 100.0));
+        assert_eq!(layers(&primitive), [(0.0, 100.0, frame.frame())]);
+    }
+
+    #[test]
+    fn frames_fill_the_widget() {
+        let frame = ImageInfo::blank(10, 10);
+        let primitive = Content::frame(1, &frame).primitive(bounds(10.0, 80.0));
+        assert_eq!(layers(&primitive), [(0.0, 80.0, frame.frame())]);
+        assert_eq!(primitive.scroll_y, 0.0);
+    }
+
+    #[test]
+    fn images_within_the_limit_stay_whole() {
+        assert_eq!(split(800, 600, 8192), [region(0, 0, 800, 600)]);
+        assert_eq!(split(8192, 8192, 8192), [region(0, 0, 8192, 8192)]);
+    }
+
+    #[test]
+    fn oversized_images_are_split_row_by_row() {
+        assert_eq!(
+            split(800, 20000, 8192),
+            [
+                region(0, 0, 800, 8192),
+                region(0, 8192, 800, 8192),
+                region(0, 16384, 800, 3616),
+            ]
+        );
+        assert_eq!(
+            split(300, 150, 100),
+            [
+                region(0, 0, 100, 100),
+                region(100, 0, 100, 100),
+                region(200, 0, 100, 100),
+                region(0, 100, 100, 50),
+                region(100, 100, 100, 50),
+                region(200, 100, 100, 50),
+            ]
+        );
+    }
+
+    #[test]
+    fn damage_is_clipped_to_the_region() {
+        let within = region(0, 100, 100, 100);
+        assert_eq!(
+            intersect(&region(10, 150, 500, 10), &within),
+            Some(region(10, 150, 90, 10))
+        );
+        assert_eq!(
+            intersect(&region(0, 90, 10, 20), &within),
+            Some(region(0, 100, 10, 10))
+        );
+        assert_eq!(intersect(&region(0, 0, 10, 100), &within), None);
+    }
+
+    #[test]
+    fn picks_the_texture_already_holding_the_region() {
+        let image = ImageInfo::blank(100, 100);
+        let whole = region(0, 0, 100, 100);
+        let slots = [slot(whole, None, 1), slot(whole, Some(image.frame()), 5)];
+        assert_eq!(pick_slot(&slots, &image, whole, 6), Some(1));
+
+        // Same frame, another region of the same size: not a match, so the
+        // least recently used texture is reused instead
+        let top = region(0, 0, 100, 50);
+        let bottom = region(0, 50, 100, 50);
+        let slots = [slot(top, Some(image.frame()), 3), slot(bottom, None, 1)];
+        assert_eq!(pick_slot(&slots, &image, top, 6), Some(0));
+        assert_eq!(pick_slot(&slots, &image, bottom, 6), Some(1));
+    }
+
+    #[test]
+    fn prefers_the_texture_holding_the_damage_base() {
+        let base = ImageInfo::blank(100, 100);
+        let image = ImageInfo::blank(100, 100).with_damage(&base, vec![region(0, 0, 10, 10)]);
+        let whole = region(0, 0, 100, 100);
+        let slots = [slot(whole, None, 1), slot(whole, Some(base.frame()), 5)];
+        assert_eq!(pick_slot(&slots, &image, whole, 6), Some(1));
+    }
+
+    #[test]
+    fn skips_textures_used_this_pass_or_of_another_size() {
+        let image = ImageInfo::blank(100, 100);
+        let whole = region(0, 0, 100, 100);
+        let slots = [
+            slot(whole, Some(image.frame()), 6),
+            slot(region(0, 0, 100, 50), None, 1),
+            slot(whole, None, 4),
+            slot(whole, None, 2),
+        ];
+        assert_eq!(pick_slot(&slots, &image, whole, 6), Some(3));
+        assert_eq!(pick_slot(&slots[..2], &image, whole, 6), None);
+    }
+}
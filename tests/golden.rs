@@ -0,0 +1,320 @@
+//! Golden-image tests: render each fixture in `tests/fixtures/golden` through
+//! the CPU engines and compare the visible viewport against the reference
+//! PNG stored next to it (`<engine>.png`).
+//!
+//! Everything is loaded from disk, so no network or GPU is needed. Set
+//! `ICED_WEBVIEW_UPDATE_GOLDEN=1` to write the references from the current
+//! output; without it a missing reference fails the test.
+#![cfg(any(feature = "litehtml", feature = "blitz"))]
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use iced::Size;
+use iced_webview::{Engine, PageType};
+use url::Url;
+
+/// A channel may differ by this much before the pixel counts as changed.
+/// Absorbs anti-aliasing and rounding differences between builds.
+const CHANNEL_TOLERANCE: u8 = 24;
+/// Share of changed pixels allowed before a fixture fails.
+const MAX_CHANGED_RATIO: f64 = 0.002;
+/// How long a fixture may take to finish loading.
+const LOAD_TIMEOUT: Duration = Duration::from_secs(60);
+
+struct Fixture {
+    name: &'static str,
+    size: Size<u32>,
+    scale: f32,
+    /// Scrolled into view before capturing.
+    fragment: Option<&'static str>,
+}
+
+const FIXTURES: &[Fixture] = &[
+    Fixture {
+        name: "tables",
+        size: Size::new(480, 320),
+        scale: 1.0,
+        fragment: None,
+    },
+    Fixture {
+        name: "email",
+        size: Size::new(420, 360),
+        scale: 1.0,
+        fragment: None,
+    },
+    Fixture {
+        name: "flexbox",
+        size: Size::new(360, 300),
+        scale: 1.0,
+        fragment: None,
+    },
+    Fixture {
+        name: "fragments",
+        size: Size::new(360, 240),
+        scale: 1.0,
+        fragment: Some("target"),
+    },
+    Fixture {
+        name: "hidpi",
+        size: Size::new(240, 180),
+        scale: 2.0,
+        fragment: None,
+    },
+];
+
+/// The visible part of a rendered view, in physical pixels.
+struct Frame {
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+}
+
+fn fixture_dir(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/golden")
+        .join(name)
+}
+
+/// Every stylesheet in the fixture directory, keyed by its `file://` URL the
+/// way litehtml looks them up. Blitz loads stylesheets itself.
+fn stylesheets(dir: &Path) -> HashMap<String, String> {
+    fs::read_dir(dir)
+        .unwrap()
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()? != "css" {
+                return None;
+            }
+            let url = Url::from_file_path(&path).ok()?;
+            Some((url.to_string(), fs::read_to_string(&path).ok()?))
+        })
+        .collect()
+}
+
+/// Drive the engine the way the widgets do until the page has loaded,
+/// serving images from the fixture directory.
+fn render<E: Engine + Default>(fixture: &Fixture) -> Frame {
+    let dir = fixture_dir(fixture.name);
+    let page = Url::from_file_path(dir.join("index.html")).unwrap();
+    let html = fs::read_to_string(dir.join("index.html")).unwrap();
+
+    let mut engine = E::default();
+    engine.set_scale_factor(fixture.scale);
+    let id = engine.new_view(fixture.size, Some(PageType::Url(page.to_string())));
+    engine.set_css_cache(id, stylesheets(&dir));
+    engine.goto(id, PageType::Html(html));
+
+    let started = Instant::now();
+    while engine.is_loading(id) {
+        assert!(
+            started.elapsed() < LOAD_TIMEOUT,
+            "{}: page did not finish loading",
+            fixture.name
+        );
+        engine.update();
+        for (view, src, baseurl, redraw_on_ready) in engine.take_pending_images() {
+            let base = Url::parse(&baseurl).unwrap_or_else(|_| page.clone());
+            let bytes = base
+                .join(&src)
+                .ok()
+                .and_then(|url| url.to_file_path().ok())
+                .and_then(|path| fs::read(path).ok());
+            match bytes {
+                Some(bytes) => engine.load_image_from_bytes(view, &src, &bytes, redraw_on_ready),
+                None => engine.image_fetch_failed(view, &src),
+            }
+        }
+        engine.request_render(id, fixture.size);
+        engine.flush_staged_images(id, fixture.size);
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    if let Some(fragment) = fixture.fragment {
+        assert!(
+            engine.scroll_to_fragment(id, fragment),
+            "{}: fragment #{fragment} not found",
+            fixture.name
+        );
+    }
+    engine.request_render(id, fixture.size);
+
+    // Both engines rasterize tiles around the scroll offset; stitch the ones
+    // covering the viewport together the way the widget draws them.
+    let top = (engine.get_scroll_y(id) * fixture.scale).round() as usize;
+    let rows = (fixture.size.height as f32 * fixture.scale) as usize;
+    let tiles = engine.get_tiles(id);
+    assert!(!tiles.is_empty(), "{}: no tiles rendered", fixture.name);
+    let width = tiles[0].image().image_width();
+    let row = width as usize * 4;
+    let mut rgba = vec![0; rows * row];
+    for tile in tiles {
+        let tile_top = (tile.top() * fixture.scale).round() as usize;
+        let pixels = tile.image().pixels();
+        for (i, src) in pixels.chunks_exact(row).enumerate() {
+            let Some(dst) = (tile_top + i).checked_sub(top).filter(|r| *r < rows) else {
+                continue;
+            };
+            rgba[dst * row..(dst + 1) * row].copy_from_slice(src);
+        }
+    }
+
+    Frame {
+        width,
+        height: rows as u32,
+        rgba,
+    }
+}
+
+fn read_png(path: &Path) -> Frame {
+    let mut decoder = png::Decoder::new(File::open(path).unwrap());
+    decoder.set_transformations(png::Transformations::EXPAND);
+    let mut reader = decoder.read_info().unwrap();
+    let mut rgba = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut rgba).unwrap();
+    assert_eq!(
+        info.color_type,
+        png::ColorType::Rgba,
+        "{}: references must be RGBA",
+        path.display()
+    );
+    rgba.truncate(info.buffer_size());
+    Frame {
+        width: info.width,
+        height: info.height,
+        rgba,
+    }
+}
+
+fn write_png(path: &Path, frame: &Frame) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let file = BufWriter::new(File::create(path).unwrap());
+    let mut encoder = png::Encoder::new(file, frame.width, frame.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().unwrap();
+    writer.write_image_data(&frame.rgba).unwrap();
+}
+
+/// Changed pixels painted red over a faded copy of the actual frame.
+fn diff_image(actual: &Frame, expected: &Frame) -> Frame {
+    let rgba = actual
+        .rgba
+        .chunks_exact(4)
+        .zip(expected.rgba.chunks_exact(4))
+        .flat_map(|(a, e)| {
+            if pixel_changed(a, e) {
+                [255, 0, 0, 255]
+            } else {
+                let fade = |c: u8| 191 + c / 4;
+                [fade(a[0]), fade(a[1]), fade(a[2]), 255]
+            }
+        })
+        .collect();
+    Frame {
+        width: actual.width,
+        height: actual.height,
+        rgba,
+    }
+}
+
+fn pixel_changed(a: &[u8], b: &[u8]) -> bool {
+    a.iter()
+        .zip(b)
+        .any(|(x, y)| x.abs_diff(*y) > CHANNEL_TOLERANCE)
+}
+
+/// Compare against the stored reference, returning a description of the
+/// mismatch if there is one.
+fn check(engine: &str, fixture: &Fixture, actual: &Frame) -> Result<(), String> {
+    let reference = fixture_dir(fixture.name).join(format!("{engine}.png"));
+    let update = std::env::var_os("ICED_WEBVIEW_UPDATE_GOLDEN").is_some();
+
+    if update {
+        write_png(&reference, actual);
+        eprintln!("golden: wrote {}", reference.display());
+        return Ok(());
+    }
+    if !reference.exists() {
+        return Err(format!(
+            "{}: no reference at {}; run with ICED_WEBVIEW_UPDATE_GOLDEN=1",
+            fixture.name,
+            reference.display()
+        ));
+    }
+
+    let expected = read_png(&reference);
+    let failures = Path::new(env!("CARGO_TARGET_TMPDIR"))
+        .join("golden")
+        .join(engine);
+    let actual_path = failures.join(format!("{}.png", fixture.name));
+
+    if (actual.width, actual.height) != (expected.width, expected.height) {
+        write_png(&actual_path, actual);
+        return Err(format!(
+            "{}: rendered {}x{}, reference is {}x{} (actual: {})",
+            fixture.name,
+            actual.width,
+            actual.height,
+            expected.width,
+            expected.height,
+            actual_path.display()
+        ));
+    }
+
+    let changed = actual
+        .rgba
+        .chunks_exact(4)
+        .zip(expected.rgba.chunks_exact(4))
+        .filter(|(a, e)| pixel_changed(a, e))
+        .count();
+    let ratio = changed as f64 / (actual.width as f64 * actual.height as f64);
+    if ratio > MAX_CHANGED_RATIO {
+        let diff_path = failures.join(format!("{}.diff.png", fixture.name));
+        write_png(&actual_path, actual);
+        write_png(&diff_path, &diff_image(actual, &expected));
+        return Err(format!(
+            "{}: {changed} pixels ({:.2}%) differ (actual: {}, diff: {})",
+            fixture.name,
+            ratio * 100.0,
+            actual_path.display(),
+            diff_path.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Render every fixture and report all mismatches at once.
+fn run<E: Engine + Default>(engine: &str) {
+    // Blitz follows the desktop color scheme; pin it so references are stable.
+    std::env::set_var("ICED_WEBVIEW_COLOR_SCHEME", "light");
+
+    let failures: Vec<String> = FIXTURES
+        .iter()
+        .filter_map(|fixture| check(engine, fixture, &render::<E>(fixture)).err())
+        .collect();
+    assert!(
+        failures.is_empty(),
+        "{engine} golden images differ:\n{}",
+        failures.join("\n")
+    );
+}
+
+#[cfg(feature = "litehtml")]
+#[test]
+fn litehtml() {
+    run::<iced_webview::Litehtml>("litehtml");
+}
+
+#[cfg(feature = "blitz")]
+#[test]
+#[ignore = "no blitz.png references recorded yet; record them with ICED_WEBVIEW_UPDATE_GOLDEN=1 and --include-ignored"]
+fn blitz() {
+    // blitz_net spawns sub-resource fetches onto the ambient tokio runtime.
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let _guard = runtime.enter();
+    run::<iced_webview::Blitz>("blitz");
+}
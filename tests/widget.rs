@@ -0,0 +1,632 @@
+//! Widget update logic, driven against `MockEngine`.
+use iced::{mouse, Point};
+use iced_webview::testing::{self, MockCall, MockEngine};
+use iced_webview::{
+    advanced, basic, Capabilities, Engine, PageType, ScriptId, ScriptValue, ViewId, WebViewError,
+    WebViewEvent,
+};
+
+#[derive(Debug, Clone)]
+enum Message {
+    Action(basic::Action),
+    UrlChanged(String),
+    TitleChanged(String),
+    Error(WebViewError),
+    Event(WebViewEvent),
+    Script(ScriptId, Result<ScriptValue, WebViewError>),
+    Copied(String),
+    Created,
+    Closed,
+}
+
+type WebView = basic::WebView<MockEngine, Message>;
+
+fn webview() -> WebView {
+    WebView::new()
+        .on_url_change(Message::UrlChanged)
+        .on_title_change(Message::TitleChanged)
+        .on_error(Message::Error)
+        .on_action(Message::Action)
+        .on_create_view(Message::Created)
+        .on_close_view(Message::Closed)
+}
+
+fn click() -> basic::Action {
+    basic::Action::SendMouseEvent(
+        mouse::Event::ButtonPressed(mouse::Button::Left),
+        Point::new(10.0, 10.0),
+    )
+}
+
+/// Run an action and feed every internal action it produces back into the
+/// widget, returning the remaining messages.
+fn settle(webview: &mut WebView, action: basic::Action) -> Vec<Message> {
+    let mut out = Vec::new();
+    let mut queue = vec![action];
+    while let Some(action) = queue.pop() {
+        for message in testing::update(webview, action) {
+            match message {
+                Message::Action(action) => queue.push(action),
+                other => out.push(other),
+            }
+        }
+    }
+    out
+}
+
+fn open(webview: &mut WebView, page: PageType) -> ViewId {
+    settle(webview, basic::Action::CreateView(page));
+    let id = *webview.engine().view_ids().last().unwrap();
+    settle(webview, basic::Action::ChangeView(0));
+    id
+}
+
+fn urls(messages: &[Message]) -> Vec<&str> {
+    messages
+        .iter()
+        .filter_map(|m| match m {
+            Message::UrlChanged(url) => Some(url.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn errors(messages: &[Message]) -> Vec<&WebViewError> {
+    messages
+        .iter()
+        .filter_map(|m| match m {
+            Message::Error(e) => Some(e),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn create_view_notifies_then_change_view_announces_url() {
+    let mut webview = webview();
+    let messages = settle(
+        &mut webview,
+        basic::Action::CreateView(PageType::Url("https://example.com/".into())),
+    );
+    assert!(matches!(messages.as_slice(), [Message::Created]));
+
+    let messages = settle(&mut webview, basic::Action::ChangeView(0));
+    assert_eq!(urls(&messages), ["https://example.com/"]);
+}
+
+#[test]
+fn change_view_announces_url_and_title() {
+    let mut webview = webview();
+    settle(
+        &mut webview,
+        basic::Action::CreateView(PageType::Html("<p>a</p>".into())),
+    );
+    let id = webview.engine().view_ids()[0];
+    webview.engine_mut().set_title(id, "A");
+    // The title event is queued for a view that isn't current yet
+    let messages = settle(&mut webview, basic::Action::ChangeView(0));
+    assert_eq!(urls(&messages), ["about:blank"]);
+    assert!(messages
+        .iter()
+        .any(|m| matches!(m, Message::TitleChanged(t) if t == "A")));
+}
+
+#[test]
+fn engine_events_reach_callbacks() {
+    let mut webview = webview();
+    let id = open(&mut webview, PageType::Html("<p>a</p>".into()));
+    webview
+        .engine_mut()
+        .set_url(id, "https://example.com/redirected");
+    webview.engine_mut().set_title(id, "Redirected");
+
+    let messages = settle(&mut webview, basic::Action::Update);
+    assert_eq!(urls(&messages), ["https://example.com/redirected"]);
+    assert!(matches!(
+        messages.as_slice(),
+        [_, Message::TitleChanged(t)] if t == "Redirected"
+    ));
+}
+
+#[test]
+fn on_event_sees_every_event() {
+    let mut webview = WebView::new().on_event(Message::Event);
+    let id = open(&mut webview, PageType::Html(String::new()));
+    webview
+        .engine_mut()
+        .push_event(id, WebViewEvent::LoadProgress(0.5));
+
+    let messages = settle(&mut webview, basic::Action::Update);
+    assert!(matches!(
+        messages.as_slice(),
+        [Message::Event(WebViewEvent::LoadProgress(p))] if *p == 0.5
+    ));
+}
+
+#[test]
+fn anchor_click_navigates() {
+    let mut webview = webview();
+    let id = open(&mut webview, PageType::Url("https://example.com/a".into()));
+    webview.engine_mut().take_calls();
+    webview.engine_mut().click_anchor(id, "/b");
+
+    // The navigation's URL change is reported with the click itself
+    let messages = settle(&mut webview, click());
+    let url = "https://example.com/b".to_string();
+    assert!(webview
+        .engine()
+        .calls()
+        .contains(&MockCall::Goto(id, PageType::Url(url.clone()))));
+    assert_eq!(urls(&messages), [url.as_str()]);
+}
+
+#[test]
+fn same_page_fragment_scrolls_instead_of_navigating() {
+    let mut webview = webview();
+    let id = open(&mut webview, PageType::Url("https://example.com/a".into()));
+    webview.engine_mut().take_calls();
+    webview.engine_mut().click_anchor(id, "#section");
+
+    settle(&mut webview, click());
+    let calls = webview.engine().calls();
+    assert!(calls.contains(&MockCall::ScrollToFragment(id, "section".into())));
+    assert!(!calls.iter().any(|c| matches!(c, MockCall::Goto(..))));
+}
+
+#[test]
+fn unresolvable_anchor_reports_invalid_url() {
+    let mut webview = webview();
+    let id = open(&mut webview, PageType::Html("<a href=x>x</a>".into()));
+    webview.engine_mut().click_anchor(id, "relative/path");
+
+    let messages = settle(&mut webview, click());
+    assert!(matches!(
+        errors(&messages).as_slice(),
+        [WebViewError::InvalidUrl(_)]
+    ));
+}
+
+#[test]
+fn copy_selection_uses_selected_text() {
+    let mut webview = webview().on_copy(Message::Copied);
+    let id = open(&mut webview, PageType::Html("<p>a</p>".into()));
+    webview
+        .engine_mut()
+        .set_selected_text(id, Some("hello".into()));
+
+    let messages = settle(&mut webview, basic::Action::CopySelection);
+    assert!(matches!(
+        messages.as_slice(),
+        [Message::Copied(t)] if t == "hello"
+    ));
+}
+
+#[test]
+fn close_view_removes_it_from_the_engine() {
+    let mut webview = webview();
+    let id = open(&mut webview, PageType::Html(String::new()));
+
+    let messages = settle(&mut webview, basic::Action::CloseView(0));
+    assert!(matches!(messages.as_slice(), [Message::Closed]));
+    assert!(!webview.engine().has_view(id));
+}
+
+#[test]
+fn queries_follow_the_current_view() {
+    let mut webview = webview();
+    let id = open(&mut webview, PageType::Html(String::new()));
+    webview.engine_mut().set_history(id, true, false);
+    webview.engine_mut().set_loading(id, true);
+
+    assert!(webview.can_go_back());
+    assert!(!webview.can_go_forward());
+    assert!(webview.is_loading());
+}
+
+#[test]
+fn scripts_without_javascript_are_unsupported() {
+    let mut webview = webview().on_script_result(Message::Script);
+    open(&mut webview, PageType::Html(String::new()));
+
+    let messages = settle(
+        &mut webview,
+        basic::Action::EvaluateScript(7, "1 + 1".into()),
+    );
+    assert!(matches!(
+        messages.as_slice(),
+        [Message::Script(7, Err(WebViewError::Unsupported(_)))]
+    ));
+}
+
+#[test]
+fn scripts_without_a_view_report_an_error() {
+    let mut webview = webview().on_script_result(Message::Script);
+
+    let messages = settle(&mut webview, basic::Action::EvaluateScript(3, "1".into()));
+    assert!(matches!(
+        messages.as_slice(),
+        [Message::Script(3, Err(WebViewError::Script(_)))]
+    ));
+}
+
+#[test]
+fn script_results_reach_on_script_result() {
+    let mut capabilities = Capabilities::default();
+    capabilities.javascript = true;
+    let mut webview = WebView::with_engine(MockEngine::default().with_capabilities(capabilities))
+        .on_script_result(Message::Script);
+    let id = open(&mut webview, PageType::Html(String::new()));
+
+    let messages = settle(
+        &mut webview,
+        basic::Action::EvaluateScript(42, "document.title".into()),
+    );
+    assert!(messages.is_empty());
+    assert!(webview.engine().calls().contains(&MockCall::EvaluateScript(
+        id,
+        42,
+        "document.title".into()
+    )));
+
+    webview.engine_mut().push_event(
+        id,
+        WebViewEvent::ScriptResult(42, Ok(ScriptValue::String("A".into()))),
+    );
+    let messages = settle(&mut webview, basic::Action::Update);
+    assert!(matches!(
+        messages.as_slice(),
+        [Message::Script(42, Ok(ScriptValue::String(t)))] if t == "A"
+    ));
+}
+
+#[cfg(feature = "litehtml")]
+mod fetch {
+    use super::*;
+    use iced_webview::{FetchMode, RetryPolicy};
+
+    fn offline() -> WebView {
+        let dir = std::env::temp_dir().join("iced_webview_empty_archive");
+        WebView::with_engine(MockEngine::default().with_handles_urls(false))
+            .on_url_change(Message::UrlChanged)
+            .on_error(Message::Error)
+            .on_action(Message::Action)
+            .fetch_mode(FetchMode::Replay(dir))
+    }
+
+    #[test]
+    fn failed_page_fetch_shows_error_page() {
+        let mut webview = offline();
+        let id = open(&mut webview, PageType::Url("https://example.com/".into()));
+        // The fetch failed inside `open`, which discards the messages, so
+        // check that the engine got the error page instead.
+        let html = webview.engine().html(id).unwrap();
+        assert!(html.contains("https://example.com/"));
+    }
+
+    #[test]
+    fn retry_link_on_the_error_page_fetches_again() {
+        let mut webview =
+            offline().error_page(|_, url, _| format!("<a href=\"{url}\">Try again</a>"));
+        let id = open(&mut webview, PageType::Url("https://example.com/".into()));
+        assert!(webview.engine().html(id).unwrap().contains("Try again"));
+        assert_eq!(webview.engine().get_url(id), "https://example.com/");
+
+        webview.engine_mut().take_calls();
+        webview
+            .engine_mut()
+            .click_anchor(id, "https://example.com/");
+        let messages = settle(&mut webview, basic::Action::Update);
+        // Still offline, so the retry fails again
+        assert!(messages
+            .iter()
+            .any(|m| matches!(m, Message::Error(WebViewError::NotInArchive(_)))));
+        assert!(webview
+            .engine()
+            .calls()
+            .iter()
+            .any(|call| matches!(call, MockCall::Goto(_, PageType::Html(html)) if html.contains("Try again"))));
+    }
+
+    /// Serve `body` with `status` to every request on a local port and
+    /// return the page URL.
+    fn serve(status: &'static str, body: &'static str) -> String {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/page.html", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    match stream.read(&mut buf) {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+            }
+        });
+        url
+    }
+
+    /// An empty archive directory unique to `name`.
+    fn archive(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("iced_webview_{name}_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn fetching(mode: FetchMode) -> WebView {
+        WebView::with_engine(MockEngine::default().with_handles_urls(false))
+            .on_error(Message::Error)
+            .on_action(Message::Action)
+            .fetch_mode(mode)
+    }
+
+    #[test]
+    fn recorded_pages_replay_offline() {
+        // reqwest needs a Tokio reactor while the fetch is polled
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let _guard = runtime.enter();
+        let url = serve("200 OK", "<p>recorded</p>");
+        let dir = archive("round_trip");
+
+        let mut webview = fetching(FetchMode::Record(dir.clone()));
+        let messages = settle(
+            &mut webview,
+            basic::Action::CreateView(PageType::Url(url.clone())),
+        );
+        assert!(errors(&messages).is_empty());
+
+        let mut webview = fetching(FetchMode::Replay(dir));
+        let messages = settle(&mut webview, basic::Action::CreateView(PageType::Url(url)));
+        assert!(errors(&messages).is_empty());
+        let id = webview.engine().view_ids()[0];
+        assert_eq!(webview.engine().html(id), Some("<p>recorded</p>"));
+    }
+
+    #[test]
+    fn replay_miss_is_reported() {
+        let mut webview = fetching(FetchMode::Replay(archive("miss")));
+        let messages = settle(
+            &mut webview,
+            basic::Action::CreateView(PageType::Url("https://example.com/missing".into())),
+        );
+        assert!(matches!(
+            errors(&messages).as_slice(),
+            [WebViewError::NotInArchive(url)] if url == "https://example.com/missing"
+        ));
+    }
+
+    #[test]
+    fn failed_record_is_reported() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let _guard = runtime.enter();
+        let url = serve("200 OK", "<p>unrecorded</p>");
+        // A file where the archive directory should be
+        let dir = archive("blocked");
+        std::fs::write(&dir, b"").unwrap();
+
+        let mut webview = fetching(FetchMode::Record(dir.clone()));
+        let messages = settle(&mut webview, basic::Action::CreateView(PageType::Url(url)));
+        let _ = std::fs::remove_file(&dir);
+        assert!(matches!(
+            errors(&messages).as_slice(),
+            [WebViewError::Record(_)]
+        ));
+    }
+
+    #[test]
+    fn error_pages_are_rendered_not_reported() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let _guard = runtime.enter();
+        let url = serve("404 Not Found", "<p>no such page</p>");
+
+        let mut webview = fetching(FetchMode::Network);
+        let messages = settle(&mut webview, basic::Action::CreateView(PageType::Url(url)));
+        assert!(errors(&messages).is_empty());
+        let id = webview.engine().view_ids()[0];
+        assert_eq!(webview.engine().html(id), Some("<p>no such page</p>"));
+    }
+
+    #[test]
+    fn error_page_hook_gets_the_view() {
+        let mut webview = offline().error_page(|id, url, _| format!("{id} {url}"));
+        let id = open(&mut webview, PageType::Url("https://example.com/".into()));
+        assert_eq!(
+            webview.engine().html(id),
+            Some(format!("{id} https://example.com/").as_str())
+        );
+    }
+
+    #[test]
+    fn failed_image_fetch_is_reported() {
+        let mut webview = offline();
+        let id = open(&mut webview, PageType::Html("<img src=a.png>".into()));
+        webview
+            .engine_mut()
+            .add_pending_image(id, "https://example.com/a.png", "", false);
+
+        let messages = settle(&mut webview, basic::Action::Update);
+        assert!(matches!(
+            errors(&messages).as_slice(),
+            [WebViewError::Image { url, error }]
+                if url == "https://example.com/a.png"
+                    && matches!(**error, WebViewError::NotInArchive(_))
+        ));
+        assert!(webview
+            .engine()
+            .calls()
+            .contains(&MockCall::ImageFetchFailed(
+                id,
+                "https://example.com/a.png".into()
+            )));
+    }
+
+    #[test]
+    fn fetch_policy_reaches_the_engine() {
+        let webview = offline().retry_policy(RetryPolicy::none());
+        let dir = std::env::temp_dir().join("iced_webview_empty_archive");
+        assert_eq!(
+            webview.engine().calls().last(),
+            Some(&MockCall::SetFetchPolicy(
+                FetchMode::Replay(dir),
+                RetryPolicy::none()
+            ))
+        );
+    }
+}
+
+mod advanced_widget {
+    use super::*;
+
+    type WebView = advanced::WebView<MockEngine, Message>;
+
+    #[test]
+    fn events_are_routed_per_view() {
+        let mut webview =
+            WebView::new().on_url_change(|id, url| Message::UrlChanged(format!("{id}:{url}")));
+        testing::update_advanced(
+            &mut webview,
+            advanced::Action::CreateView(PageType::Html(String::new())),
+        );
+        testing::update_advanced(
+            &mut webview,
+            advanced::Action::CreateView(PageType::Html(String::new())),
+        );
+        let ids = webview.engine().view_ids();
+        webview.engine_mut().set_url(ids[1], "https://example.com/");
+
+        let messages = testing::update_advanced(&mut webview, advanced::Action::UpdateAll);
+        assert_eq!(
+            urls(&messages),
+            [format!("{}:https://example.com/", ids[1]).as_str()]
+        );
+    }
+
+    #[test]
+    fn close_view_removes_it_from_the_engine() {
+        let mut webview = WebView::new();
+        testing::update_advanced(
+            &mut webview,
+            advanced::Action::CreateView(PageType::Html(String::new())),
+        );
+        let id = webview.engine().view_ids()[0];
+        testing::update_advanced(&mut webview, advanced::Action::CloseView(id));
+        assert!(webview.engine().view_ids().is_empty());
+        assert!(webview.engine().calls().contains(&MockCall::RemoveView(id)));
+    }
+}
+
+mod host {
+    use super::*;
+    use iced::Size;
+    use iced_webview::{EngineHost, Waker};
+    use std::time::{Duration, Instant};
+
+    /// Pump the host until `done` sees the events it waits for.
+    fn pump(host: &mut EngineHost<MockEngine>, mut done: impl FnMut(&[WebViewEvent]) -> bool) {
+        let started = Instant::now();
+        let mut events = Vec::new();
+        while !done(&events) {
+            assert!(started.elapsed() < Duration::from_secs(5), "no report");
+            host.update();
+            events.extend(host.take_events().into_iter().map(|e| e.event));
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn forwards_calls_and_reports_state() {
+        let mut host = EngineHost::<MockEngine>::default();
+        let size = Size::new(100, 100);
+        let id = host.new_view(size, Some(PageType::Html(String::new())));
+        host.goto(id, PageType::Url("https://example.com/".into()));
+
+        pump(&mut host, |events| {
+            events
+                .iter()
+                .any(|e| matches!(e, WebViewEvent::UrlChanged(u) if u == "https://example.com/"))
+        });
+        assert_eq!(host.get_url(id), "https://example.com/");
+        assert_eq!(host.view_ids(), [id]);
+    }
+
+    #[test]
+    fn views_report_their_capabilities() {
+        let mut capabilities = Capabilities::default();
+        capabilities.javascript = true;
+        let mut host =
+            EngineHost::new(move || MockEngine::default().with_capabilities(capabilities));
+        let id = host.new_view(Size::new(100, 100), None);
+        host.goto(id, PageType::Url("https://example.com/".into()));
+        pump(&mut host, |events| !events.is_empty());
+        assert!(host.view_capabilities(id).javascript);
+        assert!(host.evaluate_script(id, 1, "1".into()).is_ok());
+    }
+
+    #[test]
+    fn resized_views_never_show_the_old_size() {
+        let mut host = EngineHost::new(|| MockEngine::default().with_rendering(true));
+        let id = host.new_view(Size::new(100, 100), None);
+        host.request_render(id, Size::new(100, 100));
+        // Let the worker report the 100px frame before the host drains it
+        std::thread::sleep(Duration::from_millis(50));
+        host.resize(Size::new(200, 200));
+
+        for _ in 0..20 {
+            host.update();
+            assert_ne!(host.get_view(id).image_width(), 100);
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        host.request_render(id, Size::new(200, 200));
+        let started = Instant::now();
+        while host.get_view(id).image_width() != 200 {
+            assert!(started.elapsed() < Duration::from_secs(5), "no report");
+            assert_ne!(host.get_view(id).image_width(), 100);
+            host.update();
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn idle_engines_stop_waking() {
+        let mut host = EngineHost::<MockEngine>::default();
+        let waker = Waker::default();
+        host.set_waker(waker.clone());
+        host.new_view(Size::new(100, 100), Some(PageType::Html(String::new())));
+
+        // Answer every wake with an update, as the widget does, until the
+        // worker has nothing left to report
+        let started = Instant::now();
+        loop {
+            host.update();
+            std::thread::sleep(Duration::from_millis(20));
+            if !waker.take_wakeup() {
+                break;
+            }
+            assert!(started.elapsed() < Duration::from_secs(5), "never idles");
+        }
+        for _ in 0..5 {
+            host.update();
+            std::thread::sleep(Duration::from_millis(20));
+            assert!(!waker.take_wakeup());
+        }
+    }
+
+    #[test]
+    fn removed_views_are_forgotten() {
+        let mut host = EngineHost::<MockEngine>::default();
+        let id = host.new_view(Size::new(100, 100), None);
+        host.remove_view(id);
+        host.update();
+        assert!(!host.has_view(id));
+        assert!(host.view_ids().is_empty());
+    }
+}